@@ -5,17 +5,57 @@ pub trait Context <'code>: Sized {
 
 	type FunctionsIter <'a>: Iterator <Item = (usize, &'a Fn <'code>)> where 'code: 'a, Self: 'a;
 
+	type TypesIter <'a>: Iterator <Item = u32> + Clone where 'code: 'a, Self: 'a;
+
 	/// Returns the iterator of variables that are available to be used at the moment
 	fn variables <'a> (&'a self) -> Self::VariablesIter <'a>;
 
 	/// Returns the iterator of functions that are available to be used at the moment
 	fn functions <'a> (&'a self) -> Self::FunctionsIter <'a>;
 
+	/// Returns the iterator of the indices of the types(in the type bases container)
+	/// that are available to be used at the moment
+	fn types <'a> (&'a self) -> Self::TypesIter <'a>;
+
+	/// Returns the type parameters of the function the body of which is parsed
+	/// and the types they stand for in the instance being baked,
+	/// the latter are empty while the generic body itself is checked
+	fn type_arguments(&self) -> (&[u32], &[TypeIndex]);
+
+	/// Returns the type the function the body of which is parsed belongs to
+	fn owner(&self) -> Option <u32>;
+
+	/// Returns the return type of the function the body of which is parsed
+	fn return_type(&self) -> &TypeIndex;
+
 	/// Returns whether this expression is a  primary fn
 	fn is_primary_call(&self) -> bool;
 
 	/// Returns self with the change that [`is_primary_call`] should now
 	///
-	/// return` false`
+	/// return` false`, used for the arguments of calls
 	fn set_not_primary(&self) -> Self;
+
+	/// Returns whether this expression is an argument of a call, so `?` after it is applied to the call
+	fn is_argument(&self) -> bool;
+
+	/// Returns whether this expression goes after `~`, so the calls in it end at `≁`
+	fn is_after_tilde(&self) -> bool;
+
+	/// Returns self for the use after `~`: the calls in it are primary
+	/// and take the arguments till `≁` or the end of the line
+	fn enter_tilde(&self) -> Self;
+
+	/// Returns self for the use inside of braces: the calls in it are not primary
+	/// and `≁` in it does not close the `~` before the braces
+	fn enter_braces(&self) -> Self;
+
+	/// Returns self for the use inside of a nested block:
+	/// the calls in it are primary again and the variables
+	/// bound inside of it are not visible outside
+	fn enter_block(&self) -> Self;
+
+	/// Makes `variables` available to be used by everything parsed after this call,
+	/// shadowing the ones with the same names
+	fn bind_variables(&mut self, variables: Vec <TypedVariable <'code>>);
 }
@@ -0,0 +1,67 @@
+use crate::*;
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+
+///
+/// The documentation of an item, field or variant -- the doc comments written right before it
+///
+/// Example:
+/// ```text
+/// ;; Returns the sum of the numbers,
+/// ;; which should not overflow
+/// sum a b: i32 -> i32
+/// ```
+///
+#[derive(Clone, Default, Eq, PartialEq)]
+pub struct Doc <'code> {
+    /// The lines of the doc comments without `;;` and one space after it
+    pub lines: Vec <&'code str>
+}
+
+impl <'code> Debug for Doc <'code> {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        for line in &self.lines {
+            f.write_fmt(format_args!(";; {line}\n"))?
+        }
+
+        Ok(())
+    }
+}
+
+impl <'code> Doc <'code> {
+    /// The documentation of the things with no doc comments
+    pub const EMPTY: Self = Self {
+        lines: vec![]
+    };
+
+    ///
+    /// Parses the doc comments at the current position together with the empty lines between them,
+    /// the empty lines before the documented item are skipped too
+    ///
+    pub fn parse(input: &mut ParseInput <'code>) -> Self {
+        let mut lines = vec![];
+
+        remove_newlines(input);
+
+        while let Ok(token) = input.doc_comment().0 {
+            if let TokenKind::DocComment(text) = token.kind {
+                let text = &text[2..];
+                lines.push(text.strip_prefix(' ').unwrap_or(text).trim_end())
+            }
+
+            remove_newlines(input)
+        }
+
+        Self {
+            lines
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Returns the text of the documentation, its lines joined with newlines
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+}
@@ -0,0 +1,161 @@
+use core::fmt::{Debug, Formatter, Write, Result as FmtResult};
+use crate::*;
+
+///
+/// The left side of a binding -- either a `name`
+/// or a tuple of other patterns `(name (otherName anotherName))`
+///
+#[derive(Clone)]
+pub enum BindingPattern <'code> {
+    Name(Spanned <&'code str>),
+    Tuple(Vec <BindingPattern <'code>>, Span)
+}
+
+impl <'code> GetSpan for BindingPattern <'code> {
+    fn span(&self) -> Span {
+        match self {
+            Self::Name(name) => name.span,
+            Self::Tuple(_, span) => *span
+        }
+    }
+}
+
+impl <'code> Debug for BindingPattern <'code> {
+    fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+        match self {
+            Self::Name(name) => name.fmt(f),
+            Self::Tuple(patterns, _) => {
+                f.write_char('(')?;
+                print_punctuated_seq::<_, " ">(patterns.iter(), f)?;
+                f.write_char(')')
+            }
+        }
+    }
+}
+
+impl <'code> Parse <'code> for BindingPattern <'code> {
+    fn parse_impl(input: &mut ParseInput <'code>) -> Result <Self> {
+        if let Ok(open) = input.open_brace().0 {
+            let start = open.span.start;
+
+            let mut patterns = vec![];
+
+            let end = loop {
+                if let Result(Ok(t)) = input.close_brace() {
+                    break t.span.end
+                }
+
+                patterns.push(Self::parse(input)?)
+            };
+
+            return Result(Ok(Self::Tuple(patterns, Span {
+                file: input.file(),
+                start,
+                end
+            })))
+        }
+
+        Result(Ok(Self::Name(input.ident_as_spanned_str()?)))
+    }
+}
+
+impl <'code> BindingPattern <'code> {
+    /// Pushes the names the pattern binds to `names`
+    pub fn names(&self, names: &mut Vec <&'code str>) {
+        match self {
+            Self::Name(name) => names.push(name.data),
+            Self::Tuple(patterns, _) => for pattern in patterns {
+                pattern.names(names)
+            }
+        }
+    }
+
+    /// Matches the names of the pattern with the parts of the `ty`, pushing them to `variables`
+    fn bind(&self, ty: &TypeIndex, input: &ParseInput, variables: &mut Vec <TypedVariable <'code>>) -> Result <()> {
+        match self {
+            Self::Name(name) => {
+                if let Some(previous) = variables.iter().find(|v| v.name == *name) {
+                    return Result(Err(Error {
+                        span: name.span,
+                        message: format!("`{}` is bound more than once in the same pattern", name.data),
+                        clarifying: format!("first bound at {:?}", previous.name.span.start)
+                    }))
+                }
+
+                variables.push(TypedVariable {
+                    name: *name,
+                    doc: Doc::EMPTY,
+                    ty: ty.clone()
+                })
+            },
+            Self::Tuple(patterns, span) => match ty {
+                TypeIndex::Tuple(types) if types.len() == patterns.len() => for (pattern, ty) in patterns.iter().zip(types) {
+                    pattern.bind(ty, input, variables)?
+                },
+                _ => return Result(Err(Error {
+                    span: *span,
+                    message: format!("type mismatch: cannot destructure `{:?}` into a tuple of {} elements", ty.debug(input), patterns.len()),
+                    clarifying: match ty {
+                        TypeIndex::Tuple(types) => format!("expected {} names, got {}", types.len(), patterns.len()),
+                        _ => String::from("the value is not a tuple")
+                    }
+                }))
+            }
+        }
+
+        Result(Ok(()))
+    }
+}
+
+///
+/// `$pattern = value`
+///
+#[derive(Clone)]
+pub struct Binding <'code> {
+    pub pattern: BindingPattern <'code>,
+    pub value: Expr <'code>,
+    pub span: Span
+}
+
+impl <'code> GetSpan for Binding <'code> {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl <'code> ParseDebug for Binding <'code> {
+    fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
+        f.debug_struct("Binding")
+            .field("pattern", &self.pattern)
+            .field("value", &self.value.debug(input))
+            .finish()
+    }
+}
+
+impl <'code> Binding <'code> {
+    ///
+    /// Returns the binding together with the variables it introduces
+    ///
+    pub fn parse(input: &mut ParseInput <'code>, ctx: &impl Context <'code>) -> Result <(Self, Vec <TypedVariable <'code>>)> {
+        let start = input.dollar()?.span.start;
+
+        let pattern = BindingPattern::parse(input)?;
+
+        input.eq()?;
+
+        let value = Expr::parse(input, ctx)?;
+
+        let mut variables = vec![];
+        pattern.bind(&value.ty, input, &mut variables)?;
+
+        Result(Ok((Self {
+            pattern,
+            span: Span {
+                file: input.file(),
+                start,
+                end: value.span().end
+            },
+            value
+        }, variables)))
+    }
+}
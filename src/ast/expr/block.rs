@@ -0,0 +1,183 @@
+use core::fmt::{Formatter, Result as FmtResult};
+use crate::*;
+
+///
+/// The sequence of statements, each starting on a new line
+///
+/// The last statement is the value of the block,
+/// all the others should have `()` type
+///
+#[derive(Clone)]
+pub struct Block <'code> {
+    pub body: Vec <BakedFnBodyStmt <'code>>
+}
+
+impl <'code> GetSpan for Block <'code> {
+    fn span(&self) -> Span {
+        Span {
+            file: self.body.first().map(|x| x.span().file).unwrap_or(FileId::DUMMY),
+            start: self.body.first().map(|x| x.span().start).unwrap_or(CursorPosition::DEFAULT),
+            end: self.body.last().map(|x| x.span().end).unwrap_or(CursorPosition::DEFAULT)
+        }
+    }
+}
+
+impl <'code> ParseDebug for Block <'code> {
+    fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
+        print_punctuated_seq::<_, "\n">(self.body.iter().map(|i| i.debug(input)), f)
+    }
+}
+
+impl <'code> Block <'code> {
+    ///
+    /// Parses everything left in the `input` stream as the statements of the block
+    ///
+    /// The block is empty and has `()` type if there are no statements left
+    ///
+    /// Variables bound inside of the block are visible only
+    /// to the statements after the binding
+    ///
+    /// A statement that fails to parse is skipped together with the lines nested into it
+    /// and the parsing goes on from the next statement. The names of a failed binding stay unknown
+    /// till they are bound again, and the errors of the statements mentioning them are dropped,
+    /// since they are likely caused by the first one.
+    /// All the errors but the first one are reported, the first one is returned
+    ///
+    pub fn parse_stream(input: &mut ParseInput <'code>, ctx: &impl Context <'code>) -> Result <(Self, TypeIndex)> {
+        let mut ctx = ctx.enter_block();
+
+        let mut body = vec![];
+        let mut errors = vec![];
+
+        // The names of the failed bindings
+        let mut unknown = vec![];
+
+        loop {
+            remove_newlines_and_docs(input);
+
+            if input.is_exhausted() {
+                break
+            }
+
+            let start = input.get();
+            let is_binding = input.stream.buf[start].kind == TokenKind::Dollar;
+
+            let stmt = if is_binding {
+                Binding::parse(input, &ctx).0.map(|(binding, variables)| {
+                    unknown.retain(|name| variables.iter().all(|variable| variable.name.data != *name));
+                    ctx.bind_variables(variables);
+                    BakedFnBodyStmt::Binding(binding)
+                })
+            } else {
+                Expr::parse(input, &ctx).0.map(BakedFnBodyStmt::Expr)
+            };
+
+            match stmt {
+                Ok(stmt) => body.push(stmt),
+                Err(err) => {
+                    input.set(start);
+
+                    let mut names = vec![];
+                    if is_binding && input.dollar().0.is_ok() {
+                        if let Ok(pattern) = BindingPattern::parse(input).0 {
+                            pattern.names(&mut names)
+                        }
+                        input.set(start)
+                    }
+
+                    Self::skip_stmt(input);
+
+                    let mentions_unknown = input.stream.buf[start..input.get()]
+                        .iter()
+                        .any(|token| matches!(token.kind, TokenKind::Ident(ident) if unknown.contains(&ident)));
+                    if !mentions_unknown {
+                        errors.push(err)
+                    }
+
+                    unknown.extend(names)
+                }
+            }
+        }
+
+        // Check all statements(except the last one) have `()` type
+        let mut stmts_iter = body.iter();
+        let _ = stmts_iter.advance_back_by(1);
+        for stmt in stmts_iter {
+            let ty = stmt.ty();
+            if !ty.is_unit_tuple() {
+                errors.push(Error {
+                    span: stmt.span(),
+                    message: format!("type mismatch: non-return expression should have `()` type, got `{:?}`", ty.debug(input)),
+                    clarifying: String::from("help: try using `drop` function")
+                })
+            }
+        }
+
+        if !errors.is_empty() {
+            let first = errors.remove(0);
+            for err in errors {
+                input.report(err)
+            }
+            return Result(Err(first))
+        }
+
+        let ty = body.last().map(BakedFnBodyStmt::ty).unwrap_or(TypeIndex::UNIT_TUPLE);
+
+        Result(Ok((Self {
+            body
+        }, ty)))
+    }
+
+    /// Skips the statement starting at the current token, including `else` branches on separate lines
+    fn skip_stmt(input: &mut ParseInput <'code>) {
+        loop {
+            input.skip_to_next_line();
+            remove_newlines_and_docs(input);
+
+            if input.is_exhausted() || !check_if_the_next_token_is_keyword(input, &["else"]) {
+                break
+            }
+        }
+    }
+
+    ///
+    /// Parses either a single expression till the end of the current line or,
+    /// if the line ends right away, the indented block below it
+    ///
+    /// `owner` is the span of the keyword that opens the block
+    ///
+    pub fn parse_inline_or_indented(
+        input: &mut ParseInput <'code>,
+        ctx: &impl Context <'code>,
+        owner: Span
+    ) -> Result <(Self, TypeIndex)> {
+        if input.newline().0.is_err() {
+            let expr = Expr::parse(input, ctx)?;
+            let ty = expr.ty.clone();
+
+            return Result(Ok((Self {
+                body: vec![BakedFnBodyStmt::Expr(expr)]
+            }, ty)))
+        }
+
+        let block = input.find_end_of_block_and_return_everything_in_it_and_also_go_forward_to_its_end();
+
+        let old_token_stream = core::mem::replace(&mut input.stream, TokenStream::from(block));
+
+        let parsed = Self::parse_stream(input, ctx);
+
+        input.stream = old_token_stream;
+
+        let (block, ty) = parsed?;
+
+        if block.body.is_empty() {
+            return Result(Err(Error {
+                span: owner,
+                message: String::from("blocks cannot be empty"),
+                clarifying: String::from("help: indent the expressions of the block by one more level")
+            }))
+        }
+
+        Result(Ok((block, ty)))
+    }
+}
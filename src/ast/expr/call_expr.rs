@@ -52,6 +52,11 @@ impl <'code> CallExpr <'code> {
     pub fn parse(input: &mut ParseInput <'code>, ctx: &impl Context <'code>) -> Result <(Self, TypeIndex)> {
         let cur = input.get();
 
+        if check_if_the_next_token_is_keyword(input, PrimitiveExpr::KEYWORDS) {
+            let (expr, ty) = PrimitiveExpr::parse(input, ctx)?;
+            return Result(Ok((Self::Partial(Box::new(expr)), ty)))
+        }
+
         let ident = match input.ident_as_spanned_str().0 {
             Ok(ok) => ok,
             Err(_) => {
@@ -60,37 +65,406 @@ impl <'code> CallExpr <'code> {
             }
         };
 
-        let mut fun_idx = None;
-        let mut fun_args_len = None;
-        let mut fun_addr = None;
+        // `function::<T>` or `Variant::<T>`
+        let type_args = Self::parse_type_args(input, ctx)?;
+
+        // The index of the function in `ParseInput.top_level_items` and the function itself
+        let mut found = None;
+
+        // `Type::function` or `Enum::Variant`
+        if type_args.is_none() && input.colon_colon().0.is_ok() {
+            let mut ty = ident;
+            let mut name = input.ident_as_spanned_str()?;
+            let mut ty_idx = input.find_type(ident.data);
+
+            let module = match ty_idx {
+                Some(_) => None,
+                None => input.imported_module(ident.data)
+            };
+
+            if let Some(module) = module {
+                if input.colon_colon().0.is_ok() {
+                    // `module::Type::function` or `module::Enum::Variant`
+                    ty = name;
+                    name = input.ident_as_spanned_str()?;
+                    ty_idx = input.find_type_in(Some(module), ty.data)
+                } else {
+                    // `module::function`
+                    found = Some(Self::resolve_in_module(ctx, (ident, module), name)?)
+                }
+            }
+
+            if found.is_none() {
+                match Self::resolve_member(input, ctx, (ty, ty_idx), name)? {
+                    Some(x) => found = Some(x),
+                    None => {
+                        // The variant is always found when the enum is given
+                        let variant = VariantExpr::resolve(input, ctx, Some((ty, ty_idx)), name)?.unwrap();
 
-        if let Some(x) = ctx.functions().find(|(_, fun)| fun.name == ident) {
-            fun_idx = Some(x.0);
-            fun_args_len = Some(x.1.args.len());
-            fun_addr = Some(x.1 as *const Fn);
+                        let type_args = Self::parse_type_args(input, ctx)?;
+
+                        return Self::parse_variant(input, ctx, cur, ident, name, variant, type_args)
+                    }
+                }
+            }
+        } else {
+            found = ctx.functions().find(|(_, fun)| fun.name == ident && input.is_fn_in_scope(fun))
         }
 
-        if fun_idx.is_none() {
+        let (fun_idx, fun) = match found {
+            Some(x) => x,
+            None => {
+                if let Some(variant) = VariantExpr::resolve(input, ctx, None, ident)? {
+                    return Self::parse_variant(input, ctx, cur, ident, ident, variant, type_args)
+                }
+
+                input.set(cur);
+
+                let (expr, ty) = match PrimitiveExpr::parse(input, ctx).0 {
+                    Ok(ok) => ok,
+                    // The variable is there, so the error is in what follows it, like an access to a field
+                    Err(err) if ctx.variables().any(|variable| variable.name == ident) => return Result(Err(err)),
+                    _ => return Result(Err(Error {
+                        span: ident.span,
+                        message: format!("`{}` is not a function", ident.data),
+                        clarifying: String::from("here")
+                    }))
+                };
+
+                return Result(Ok((Self::Partial(Box::new(expr)), ty)))
+            }
+        };
+
+        // The type arguments of `Type::function` go after the function
+        let type_args = match (fun.owner, type_args) {
+            (Some(_), None) => Self::parse_type_args(input, ctx)?,
+            (_, type_args) => type_args
+        };
+
+        let (call, ret_ty) = Self::parse_call(input, ctx, cur, ident, (fun_idx, fun), type_args, None)?;
+
+        Result(Ok((Self::Full(Box::new(call)), ret_ty)))
+    }
+
+    ///
+    /// Parses the call of the method `name` on the `receiver` -- `value.method`,
+    /// `fun` is the method with its index, `cur` is where the call started
+    ///
+    pub fn parse_method(
+        input: &mut ParseInput <'code>,
+        ctx: &impl Context <'code>,
+        cur: usize,
+        name: Spanned <&'code str>,
+        fun: (usize, &Fn <'code>),
+        receiver: Expr <'code>
+    ) -> Result <(PrimitiveExpr <'code>, TypeIndex)> {
+        let type_args = Self::parse_type_args(input, ctx)?;
+
+        // A method takes exactly its arguments, so that it can be used like a field: `(a.len, b.len)`
+        let ctx = ctx.set_not_primary();
+
+        let (call, ret_ty) = Self::parse_call(input, &ctx, cur, name, fun, type_args, Some(receiver))?;
+
+        Result(Ok((PrimitiveExpr::MethodCall(Box::new(call)), ret_ty)))
+    }
+
+    ///
+    /// Parses the arguments of the call of the function `fun` with the index `fun_idx`
+    /// and instantiates it if it is generic, returning the call and the type of its result
+    ///
+    /// The `receiver` of a method is its first argument and the start of the call
+    ///
+    fn parse_call(
+        input: &mut ParseInput <'code>,
+        ctx: &impl Context <'code>,
+        cur: usize,
+        name: Spanned <&'code str>,
+        (fun_idx, fun): (usize, &Fn <'code>),
+        type_args: Option <Spanned <Vec <TypeIndex>>>,
+        receiver: Option <Expr <'code>>
+    ) -> Result <(CallExprFull <'code>, TypeIndex)> {
+        let mut inferred = Self::given_type_args(name, &fun.type_params, type_args)?;
+
+        let expected = fun.args.iter().map(|arg| arg.ty.clone()).collect::<Vec <_>>();
+
+        let (start, mut args) = match receiver {
+            Some(receiver) => {
+                // The method is found by the type of the receiver, so it always fits
+                let fits = expected[0].infer(input, &receiver.ty, &fun.type_params, &mut inferred);
+                debug_assert!(fits);
+
+                (receiver.span().start, vec![receiver])
+            },
+            None => (name.span.start, vec![])
+        };
+
+        let written = Self::parse_args(
+            input,
+            ctx,
+            cur,
+            name.span,
+            &expected[args.len()..],
+            &fun.type_params,
+            &mut inferred
+        )?;
+
+        let end = written.last().map(|x| x.span().end).unwrap_or(name.span.end);
+
+        args.extend(written);
+
+        let type_args = Self::inferred_type_args(input, name, &fun.type_params, inferred)?;
+
+        let ret_ty = fun.ret_ty.substitute(input, &fun.type_params, &type_args);
+
+        let fun_idx = Self::instantiate(input, name, (fun_idx, fun), type_args)?;
+
+        Result(Ok((CallExprFull {
+            fun: fun_idx,
+            span: Span {
+                file: input.file(),
+                start,
+                end
+            },
+            args
+        }, ret_ty)))
+    }
+
+    ///
+    /// Returns the index of the instance of the function `fun` with the index `fun_idx` for the `type_args`,
+    /// `name` is where the function is called
+    ///
+    /// The generic functions called from the bodies of other generic ones with their type parameters
+    /// are instantiated when the callers are, with the concrete types
+    ///
+    pub fn instantiate(
+        input: &mut ParseInput <'code>,
+        name: Spanned <&'code str>,
+        (fun_idx, fun): (usize, &Fn <'code>),
+        type_args: Vec <TypeIndex>
+    ) -> Result <usize> {
+        if !fun.is_generic() || !type_args.iter().all(TypeIndex::is_concrete) {
+            return Result(Ok(fun_idx))
+        }
+
+        match input.fn_instances.instantiate(fun_idx, type_args) {
+            Some(idx) => Result(Ok(idx)),
+            None => Result(Err(Error {
+                span: name.span,
+                message: format!("`{}` is instantiated with too many or too long types", name.data),
+                clarifying: String::from("help: check that it does not call itself with the types growing with every call")
+            }))
+        }
+    }
+
+    ///
+    /// Finds the function `name` of the type named `ty` with the index `ty_idx` -- `Type::function`,
+    /// returning `None` if there is no such type or it is an enum with no such function, but maybe with such variant
+    ///
+    /// It is an error if the type has no such function or if it is private and called
+    /// not from a function of the same type
+    ///
+    fn resolve_member <'a> (
+        input: &ParseInput <'code>,
+        ctx: &'a impl Context <'code>,
+        (ty, ty_idx): (Spanned <&'code str>, Option <u32>),
+        name: Spanned <&'code str>
+    ) -> Result <Option <(usize, &'a Fn <'code>)>> {
+        let baked = match &input.type_bases {
+            TypeBaseContainer::Baked(baked) => baked,
+            _ => unreachable!()
+        };
+
+        let ty_idx = match ty_idx {
+            Some(idx) => idx,
+            None => return Result(Ok(None))
+        };
+
+        let error = |message: String, clarifying: String| Result(Err(Error {
+            span: name.span,
+            message,
+            clarifying
+        }));
+
+        let (idx, fun) = match ctx.functions().find(|(_, fun)| fun.name == name && fun.owner == Some(ty_idx)) {
+            Some(x) => x,
+            // The variants of enums are looked for the same way
+            None if matches!(baked[ty_idx as usize].kind, BakedTypeBaseKind::TypeSum(_)) => return Result(Ok(None)),
+            None => return error(format!("no function named `{}` in the type `{}`", name.data, ty.data), String::from("here"))
+        };
+
+        if fun.visibility == Visibility::Private && ctx.owner() != Some(ty_idx) {
+            return error(
+                format!("the function `{}::{}` is private", ty.data, name.data),
+                format!("it can be called only by the functions of `{}`", ty.data)
+            )
+        }
+
+        Result(Ok(Some((idx, fun))))
+    }
+
+    /// Finds the function `name` of the `module` imported as `path` -- `module::function`
+    fn resolve_in_module <'a> (
+        ctx: &'a impl Context <'code>,
+        (path, module): (Spanned <&'code str>, u32),
+        name: Spanned <&'code str>
+    ) -> Result <(usize, &'a Fn <'code>)> {
+        match ctx.functions().find(|(_, fun)| fun.name == name && fun.owner.is_none() && fun.module == module) {
+            Some(x) => Result(Ok(x)),
+            None => Result(Err(Error {
+                span: name.span,
+                message: format!("no function named `{}` in the module `{}`", name.data, path.data),
+                clarifying: String::from("here")
+            }))
+        }
+    }
+
+    fn parse_variant(
+        input: &mut ParseInput <'code>,
+        ctx: &impl Context <'code>,
+        cur: usize,
+        start: Spanned <&'code str>,
+        name: Spanned <&'code str>,
+        (ty, variant): (u32, usize),
+        type_args: Option <Spanned <Vec <TypeIndex>>>
+    ) -> Result <(Self, TypeIndex)> {
+        let (payload, params) = match &input.type_bases {
+            TypeBaseContainer::Baked(baked) => match &baked[ty as usize].kind {
+                BakedTypeBaseKind::TypeSum(variants) => (variants.vec[variant].payload.clone(), baked[ty as usize].params.clone()),
+                _ => unreachable!()
+            },
+            _ => unreachable!()
+        };
+
+        let mut inferred = Self::given_type_args(name, &params, type_args)?;
+
+        let args = Self::parse_args(input, ctx, cur, name.span, &payload, &params, &mut inferred)?;
+
+        let type_args = Self::inferred_type_args(input, name, &params, inferred)?;
+
+        // The value of a generic enum is of its instance, unless the type arguments depend on type parameters
+        let (ty, value_ty) = if params.is_empty() {
+            (ty, TypeIndex::Scalar(ty))
+        } else {
+            match TypeIndex::Applied(ty, type_args).concretize(input) {
+                TypeIndex::Scalar(instance) => (instance, TypeIndex::Scalar(instance)),
+                applied => (ty, applied)
+            }
+        };
+
+        Result(Ok((Self::Partial(Box::new(PrimitiveExpr::Variant(Box::new(VariantExpr {
+            ty,
+            variant,
+            span: Span {
+                file: start.span.file,
+                start: start.span.start,
+                end: args.last().map(|x| x.span().end).unwrap_or(name.span.end)
+            },
+            args
+        })))), value_ty)))
+    }
+
+    ///
+    /// Parses the explicitly given type arguments -- `::<i32, T>`, if there are any
+    ///
+    fn parse_type_args(input: &mut ParseInput <'code>, ctx: &impl Context <'code>) -> Result <Option <Spanned <Vec <TypeIndex>>>> {
+        let cur = input.get();
+
+        let start = match input.colon_colon().0 {
+            Ok(colon_colon) => colon_colon.span.start,
+            Err(_) => return Result(Ok(None))
+        };
+
+        if input.less().0.is_err() {
             input.set(cur);
+            return Result(Ok(None))
+        }
 
-            let (expr, ty) = match PrimitiveExpr::parse(input, ctx).0 {
-                Ok(ok) => ok,
-                _ => return Result(Err(Error {
-                    span: ident.span,
-                    message: format!("`{}` is not a function", ident.data),
-                    clarifying: String::from("here"),
-                    filename: input.filename.to_string(),
-                    code: input.code.to_string()
-                }))
-            };
+        let types = Punctuated::<TypeIndex, "">::new(input, ParseInput::comma, ParseInput::greater)?.vec;
+
+        let span = Span {
+            file: input.file(),
+            start,
+            end: input.stream.buf[input.get() - 1].span.end
+        };
+
+        let (params, args) = ctx.type_arguments();
+
+        let mut substituted = vec![];
+
+        for ty in types {
+            ty.check_type_args(input, span)?;
+            substituted.push(ty.substitute(input, params, args))
+        }
+
+        Result(Ok(Some(Spanned {
+            data: substituted,
+            span
+        })))
+    }
+
+    ///
+    /// Returns the type arguments for the type parameters `params` of `name` known before its arguments are parsed:
+    /// all of them if they are given explicitly, none otherwise
+    ///
+    fn given_type_args(
+        name: Spanned <&'code str>,
+        params: &[u32],
+        given: Option <Spanned <Vec <TypeIndex>>>
+    ) -> Result <Vec <Option <TypeIndex>>> {
+        let given = match given {
+            Some(given) => given,
+            None => return Result(Ok(vec![None; params.len()]))
+        };
+
+        if given.data.len() != params.len() {
+            return Result(Err(Error {
+                span: given.span,
+                message: format!("wrong number of type arguments for `{}`", name.data),
+                clarifying: format!("expected `{}`, got `{}`", params.len(), given.data.len())
+            }))
+        }
+
+        Result(Ok(given.data.into_iter().map(Some).collect()))
+    }
+
+    /// Returns the inferred type arguments for the type parameters `params` of `name`, all of them should be known
+    pub fn inferred_type_args(
+        input: &ParseInput <'code>,
+        name: Spanned <&'code str>,
+        params: &[u32],
+        inferred: Vec <Option <TypeIndex>>
+    ) -> Result <Vec <TypeIndex>> {
+        if let Some(idx) = inferred.iter().position(Option::is_none) {
+            let names = params.iter().map(|param| input.type_params[*param as usize].data).collect::<Vec <_>>();
 
-            return Result(Ok((Self::Partial(Box::new(expr)), ty)));
+            return Result(Err(Error {
+                span: name.span,
+                message: format!("cannot infer the type parameter `{}` of `{}`", names[idx], name.data),
+                clarifying: format!("help: give the type arguments explicitly, like `{}::<{}>`", name.data, names.join(", "))
+            }))
         }
 
-        let fun_idx = unsafe { fun_idx.unwrap_unchecked() };
-        let fun_args_len = unsafe { fun_args_len.unwrap_unchecked() };
-        let fun_addr = unsafe { fun_addr.unwrap_unchecked() };
+        Result(Ok(inferred.into_iter().flatten().collect()))
+    }
 
+    ///
+    /// Parses the arguments of a call of something taking arguments of `expected` types,
+    /// checking their number and types
+    ///
+    /// The type parameters `params` of the called thing are inferred from the types of the arguments,
+    /// the types they stand for are put into `inferred`
+    ///
+    /// `cur` is where the call started and `name` is the span of the name of the called thing
+    ///
+    fn parse_args(
+        input: &mut ParseInput <'code>,
+        ctx: &impl Context <'code>,
+        cur: usize,
+        name: Span,
+        expected: &[TypeIndex],
+        params: &[u32],
+        inferred: &mut [Option <TypeIndex>]
+    ) -> Result <Vec <Expr <'code>>> {
         let mut args = vec![];
 
         let ctx_for_exprs = ctx.set_not_primary();
@@ -99,17 +473,77 @@ impl <'code> CallExpr <'code> {
             // Let non-top-level function parse only their arguments number,
             // Propagate everything to it otherwise
             if !ctx.is_primary_call() {
-                if args.len() == fun_args_len {
+                if args.len() == expected.len() {
                     break
                 }
             }
 
-            // SAFETY: exhaustiveness check was performed earlier
-            if unsafe { check_if_the_next_token_is_newline(input) } {
+            if check_if_the_next_token_is_newline(input) {
+                break
+            }
+
+            // The arguments of a call inside of `if` end where its next part starts
+            if check_if_the_next_token_is_keyword(input, &["then", "else"]) {
+                break
+            }
+
+            // `value.method.other` calls `other` on the result of `method`
+            if input.stream.buf[input.get()].kind == TokenKind::Dot {
+                break
+            }
+
+            // `f x ?` applies `?` to the result of the call
+            if input.stream.buf[input.get()].kind == TokenKind::Question {
+                break
+            }
+
+            // No expression starts with a comma or a closing brace, so they end the calls inside tuples, `(f 1 ~ g 2, h 3)`
+            if matches!(input.stream.buf[input.get()].kind, TokenKind::Comma | TokenKind::CloseBrace) {
+                break
+            }
+
+            // The arguments after `~` end at `≁`
+            if ctx.is_after_tilde() && input.stream.buf[input.get()].kind == TokenKind::NotTilde {
                 break
             }
 
-            let expr = match Expr::parse(input, &ctx_for_exprs).0 {
+            if let Ok(not_tilde) = input.not_tilde().0 {
+                input.set(cur);
+                return Result(Err(Error {
+                    span: not_tilde.span,
+                    message: String::from("unmatched `≁`"),
+                    clarifying: String::from("there is no `~` before it to close")
+                }))
+            }
+
+            let expr = if let Ok(tilde) = input.tilde().0 {
+                if input.is_exhausted() || check_if_the_next_token_is_newline(input) || matches!(
+                    input.stream.buf[input.get()].kind,
+                    TokenKind::NotTilde | TokenKind::CloseBrace | TokenKind::Comma
+                ) {
+                    let span = tilde.span;
+                    input.set(cur);
+                    return Result(Err(Error {
+                        span,
+                        message: String::from("`~` needs a call after it"),
+                        clarifying: String::from("help: remove it or write the call it stands for")
+                    }))
+                }
+
+                // `a ~ b c` is `a (b c)`
+                let expr = Expr::parse(input, &ctx.enter_tilde());
+
+                // `≁` closes all the `~` at once, so only the call before the first of them takes it
+                if expr.0.is_ok() && !ctx.is_after_tilde() {
+                    let _ = input.not_tilde();
+                }
+
+                expr
+            } else {
+                Expr::parse(input, &ctx_for_exprs)
+            };
+
+            let expr = match expr.0 {
                 Ok(ok) => ok,
                 Err(err) => {
                     input.set(cur);
@@ -120,38 +554,33 @@ impl <'code> CallExpr <'code> {
             args.push(expr)
         }
 
-        let fun = unsafe { &*fun_addr };
-
-        if args.len() != fun_args_len {
+        if args.len() != expected.len() {
             input.set(cur);
             return Result(Err(Error {
-                span: ident.span,
+                span: name,
                 message: String::from("wrong number of arguments"),
-                clarifying: format!("expected `{}`, got `{}`", fun_args_len, args.len()),
-                filename: input.filename.to_string(),
-                code: input.code.to_string()
+                clarifying: format!("expected `{}`, got `{}`", expected.len(), args.len())
             }))
         }
 
-        for (parsed, native) in args.iter().zip(&fun.args) {
-            if parsed.ty != native.ty {
+        for (parsed, native) in args.iter().zip(expected) {
+            if !native.infer(input, &parsed.ty, params, inferred) {
+                // The type parameters inferred so far are shown as the types they stand for
+                let known = params
+                    .iter()
+                    .zip(inferred.iter())
+                    .map(|(param, ty)| ty.clone().unwrap_or(TypeIndex::Generic(*param)))
+                    .collect::<Vec <_>>();
+                let native = native.replace_params(params, &known);
+
                 return Result(Err(Error {
                     span: parsed.span(),
                     message: String::from("wrong type of the argument"),
-                    clarifying: format!("expected `{:?}`, got `{:?}`", native.ty.debug(input), parsed.ty.debug(input)),
-                    filename: input.filename.to_string(),
-                    code: input.code.to_string()
+                    clarifying: format!("expected `{:?}`, got `{:?}`", native.debug(input), parsed.ty.debug(input))
                 }))
             }
         }
 
-        Result(Ok((Self::Full(Box::new(CallExprFull {
-            fun: fun_idx,
-            span: Span {
-                start: ident.span.start,
-                end: args.last().map(|x| x.span().end).unwrap_or(ident.span.end)
-            },
-            args
-        })), fun.ret_ty.clone())))
+        Result(Ok(args))
     }
 }
@@ -16,6 +16,21 @@ macro_rules! define_expr {
             }
         }
 
+        impl <'code> From <PrimitiveExpr <'code>> for $name <'code> {
+            fn from(primitive: PrimitiveExpr <'code>) -> Self {
+                Self::Partial(Box::new(primitive.into()))
+            }
+        }
+
+        impl <'code> AsExprNode <'code> for $name <'code> {
+            fn node(&self) -> ExprNode <'_, 'code> {
+                match self {
+                    Self::Full(full) => full.node(),
+                    Self::Partial(partial) => partial.node()
+                }
+            }
+        }
+
         impl <'code> ParseDebug for $name <'code> {
             fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
                 f.write_str(concat!(stringify!($name), "::"))?;
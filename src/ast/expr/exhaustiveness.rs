@@ -0,0 +1,287 @@
+use crate::*;
+
+/// The wildcard standing for the parts of values no pattern looks into
+static WILDCARD: Pattern <'static> = Pattern::Wildcard(Span::DEFAULT);
+
+/// At most this many missing patterns are shown
+const MAX_WITNESSES: usize = 3;
+
+///
+/// The way a value of some type is built, as seen by the exhaustiveness check
+///
+#[derive(Clone, PartialEq)]
+enum Constructor {
+    /// The only way to build a tuple or a `ty` type
+    Single,
+    Variant(usize),
+    Bool(bool),
+    Int(i128)
+}
+
+///
+/// A value that is not matched by the patterns, the form it is reported in
+///
+#[derive(Clone)]
+enum Witness {
+    Wildcard,
+    Constructor(Constructor, TypeIndex, Vec <Witness>)
+}
+
+impl Witness {
+    fn show(&self, input: &ParseInput, nested: bool) -> String {
+        let (constructor, ty, args) = match self {
+            Self::Wildcard => return String::from("_"),
+            Self::Constructor(constructor, ty, args) => (constructor, ty, args)
+        };
+
+        // The elements of tuples are separated by commas, so they need no braces
+        let is_tuple = matches!(ty, TypeIndex::Tuple(_));
+        let args = args.iter().map(|arg| arg.show(input, !is_tuple)).collect::<Vec <_>>();
+
+        let name = match (constructor, ty) {
+            (Constructor::Bool(bool), _) => return bool.to_string(),
+            (Constructor::Int(int), _) => return int.to_string(),
+            (Constructor::Single, TypeIndex::Tuple(_)) => return match args.len() {
+                1 => format!("({},)", args[0]),
+                _ => format!("({})", args.join(", "))
+            },
+            (Constructor::Single, ty) => match base_of(input, ty) {
+                Some((base, _)) => base.name.data,
+                None => unreachable!()
+            },
+            (Constructor::Variant(variant), ty) => match base_of(input, ty).map(|(base, _)| &base.kind) {
+                Some(BakedTypeBaseKind::TypeSum(variants)) => variants.vec[*variant].name.data,
+                _ => unreachable!()
+            }
+        };
+
+        match (args.is_empty(), nested) {
+            (true, _) => String::from(name),
+            (false, false) => format!("{name} {}", args.join(" ")),
+            (false, true) => format!("({name} {})", args.join(" "))
+        }
+    }
+}
+
+///
+/// The result of the analysis of the arms of `match`
+///
+pub struct Usefulness {
+    /// The values not matched by any arm, shown as patterns
+    pub missing: Vec <String>,
+
+    /// Whether there are more missing values than shown in `missing`
+    pub more_missing: bool,
+
+    /// The indices of the arms that never match, since the ones before them cover everything they do
+    pub unreachable: Vec <usize>
+}
+
+impl Usefulness {
+    ///
+    /// Checks which values of the type `ty` are not matched by any of `patterns`
+    /// and which of `patterns` are covered by the ones before them
+    ///
+    pub fn check(input: &ParseInput, ty: &TypeIndex, patterns: &[&Pattern]) -> Self {
+        let types = [ty.clone()];
+        let mut rows: Vec <Vec <&Pattern>> = vec![];
+        let mut unreachable = vec![];
+
+        for (idx, pattern) in patterns.iter().enumerate() {
+            let row = vec![*pattern];
+
+            if witnesses(input, &rows, &row, &types).is_empty() {
+                unreachable.push(idx)
+            }
+
+            rows.push(row)
+        }
+
+        let mut missing = witnesses(input, &rows, &[&WILDCARD], &types)
+            .into_iter()
+            .map(|mut witness| witness.remove(0).show(input, false))
+            .collect::<Vec <_>>();
+
+        let more_missing = missing.len() > MAX_WITNESSES;
+        missing.truncate(MAX_WITNESSES);
+
+        Self {
+            missing,
+            more_missing,
+            unreachable
+        }
+    }
+}
+
+/// Returns the constructor the pattern starts with, `None` for the ones matching everything
+fn constructor_of(pattern: &Pattern) -> Option <Constructor> {
+    match pattern {
+        Pattern::Wildcard(_) | Pattern::Binding(_) => None,
+        Pattern::Int(int) => Some(Constructor::Int(int.data)),
+        Pattern::Bool(bool) => Some(Constructor::Bool(bool.data)),
+        Pattern::Tuple(..) | Pattern::Product { .. } => Some(Constructor::Single),
+        Pattern::Variant { variant, .. } => Some(Constructor::Variant(*variant))
+    }
+}
+
+/// Returns the type base of `ty` together with the types its type parameters stand for
+fn base_of <'a, 'code> (input: &'a ParseInput <'code>, ty: &'a TypeIndex) -> Option <(&'a BakedTypeBase <'code>, &'a [TypeIndex])> {
+    let (idx, args) = ty.base()?;
+
+    match &input.type_bases {
+        TypeBaseContainer::Baked(baked) => Some((&baked[idx as usize], args)),
+        _ => unreachable!()
+    }
+}
+
+/// Returns the types of the parts the `constructor` of the type `ty` builds its value from
+fn fields_of(input: &ParseInput, constructor: &Constructor, ty: &TypeIndex) -> Vec <TypeIndex> {
+    match (constructor, ty) {
+        (Constructor::Single, TypeIndex::Tuple(types)) => types.clone(),
+        (Constructor::Single, ty) => match base_of(input, ty) {
+            Some((base, args)) => match &base.kind {
+                BakedTypeBaseKind::TypeProduct(fields) => fields.vec.iter().map(|field| field.ty.replace_params(&base.params, args)).collect(),
+                _ => unreachable!()
+            },
+            _ => unreachable!()
+        },
+        (Constructor::Variant(variant), ty) => match base_of(input, ty) {
+            Some((base, args)) => match &base.kind {
+                BakedTypeBaseKind::TypeSum(variants) => variants.vec[*variant].payload.iter().map(|ty| ty.replace_params(&base.params, args)).collect(),
+                _ => unreachable!()
+            },
+            _ => unreachable!()
+        },
+        (Constructor::Bool(_) | Constructor::Int(_), _) => vec![]
+    }
+}
+
+/// Returns all the constructors of the type `ty`, `None` if there are too many of them to list
+fn all_constructors(input: &ParseInput, ty: &TypeIndex) -> Option <Vec <Constructor>> {
+    // The values of type parameters can be anything
+    let base = match ty {
+        TypeIndex::Tuple(_) => return Some(vec![Constructor::Single]),
+        _ => base_of(input, ty)?.0
+    };
+
+    match &base.kind {
+        BakedTypeBaseKind::Builtin(_) if base.name.data == "bool" => Some(vec![Constructor::Bool(false), Constructor::Bool(true)]),
+        BakedTypeBaseKind::Builtin(_) => None,
+        BakedTypeBaseKind::TypeProduct(_) => Some(vec![Constructor::Single]),
+        BakedTypeBaseKind::TypeSum(variants) => Some((0..variants.vec.len()).map(Constructor::Variant).collect())
+    }
+}
+
+///
+/// Replaces the first pattern of the `row` by the patterns of its parts if it is built by `constructor`
+/// (or matches everything), returns `None` if the row cannot match values built by `constructor`
+///
+fn specialize <'a, 'code> (row: &[&'a Pattern <'code>], constructor: &Constructor, arity: usize) -> Option <Vec <&'a Pattern <'code>>> {
+    let mut specialized = match row[0] {
+        Pattern::Wildcard(_) | Pattern::Binding(_) => vec![&WILDCARD; arity],
+        Pattern::Tuple(patterns, _) | Pattern::Product { fields: patterns, .. } => patterns.iter().collect(),
+        pattern @ Pattern::Variant { args, .. } if constructor_of(pattern).as_ref() == Some(constructor) => args.iter().collect(),
+        pattern if constructor_of(pattern).as_ref() == Some(constructor) => vec![],
+        _ => return None
+    };
+
+    specialized.extend_from_slice(&row[1..]);
+    Some(specialized)
+}
+
+///
+/// Returns the values of the `types` that are matched by the `row` but not by any of the `rows`,
+/// empty if there are none and so the `row` is useless after the `rows`
+///
+/// It is the usefulness algorithm from "Warnings for pattern matching" by Luc Maranget
+///
+fn witnesses(input: &ParseInput, rows: &[Vec <&Pattern>], row: &[&Pattern], types: &[TypeIndex]) -> Vec <Vec <Witness>> {
+    if row.is_empty() {
+        return if rows.is_empty() {
+            vec![vec![]]
+        } else {
+            vec![]
+        }
+    }
+
+    let ty = &types[0];
+
+    // The witnesses for the values built by `constructor`
+    let specialized = |constructor: &Constructor| -> Vec <Vec <Witness>> {
+        let mut sub_types = fields_of(input, constructor, ty);
+        let arity = sub_types.len();
+        sub_types.extend_from_slice(&types[1..]);
+
+        let row = match specialize(row, constructor, arity) {
+            Some(row) => row,
+            None => return vec![]
+        };
+        let rows = rows.iter().filter_map(|other| specialize(other, constructor, arity)).collect::<Vec <_>>();
+
+        witnesses(input, &rows, &row, &sub_types).into_iter().map(|mut witness| {
+            let args = witness.drain(..arity).collect();
+            witness.insert(0, Witness::Constructor(constructor.clone(), ty.clone(), args));
+            witness
+        }).collect()
+    };
+
+    if let Some(constructor) = constructor_of(row[0]) {
+        return specialized(&constructor)
+    }
+
+    let used = rows.iter().filter_map(|other| constructor_of(other[0])).collect::<Vec <_>>();
+
+    // If every constructor is used by some row, the values built by each of them are checked separately
+    let all = all_constructors(input, ty);
+
+    if let Some(all) = all.as_ref().filter(|all| all.iter().all(|constructor| used.contains(constructor))) {
+        let mut result = vec![];
+
+        for constructor in all {
+            result.extend(specialized(constructor));
+
+            if result.len() > MAX_WITNESSES {
+                break
+            }
+        }
+
+        return result
+    }
+
+    // Otherwise only the rows matching everything matter for the unused constructors
+    let rows = rows.iter().filter(|other| constructor_of(other[0]).is_none()).map(|other| other[1..].to_vec()).collect::<Vec <_>>();
+
+    let tails = witnesses(input, &rows, &row[1..], &types[1..]);
+
+    if tails.is_empty() {
+        return vec![]
+    }
+
+    let missing = match all {
+        Some(all) if !used.is_empty() => all
+            .into_iter()
+            .filter(|constructor| !used.contains(constructor))
+            .map(|constructor| {
+                let args = vec![Witness::Wildcard; fields_of(input, &constructor, ty).len()];
+                Witness::Constructor(constructor, ty.clone(), args)
+            })
+            .collect(),
+        _ => vec![Witness::Wildcard]
+    };
+
+    let mut result = vec![];
+
+    for head in missing {
+        for tail in &tails {
+            let mut witness = vec![head.clone()];
+            witness.extend_from_slice(tail);
+            result.push(witness)
+        }
+
+        if result.len() > MAX_WITNESSES {
+            break
+        }
+    }
+
+    result
+}
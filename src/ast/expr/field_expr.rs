@@ -0,0 +1,176 @@
+use core::fmt::{Formatter, Result as FmtResult};
+use crate::*;
+
+///
+/// Access to a field of a value of a `ty` type -- `value.field`
+///
+#[derive(Clone)]
+pub struct FieldExpr <'code> {
+    pub value: PrimitiveExpr <'code>,
+    pub name: Spanned <&'code str>,
+
+    /// The index of the field in the type
+    pub field: usize,
+
+    /// The type of the field
+    pub ty: TypeIndex,
+    pub span: Span
+}
+
+impl <'code> GetSpan for FieldExpr <'code> {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl <'code> ParseDebug for FieldExpr <'code> {
+    fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
+        f.debug_struct("FieldExpr")
+            .field("value", &self.value.debug(input))
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl <'code> FieldExpr <'code> {
+    ///
+    /// Parses the accesses to the fields and the calls of the methods following the `value` of the type `ty`,
+    /// if there are any, returning the resulting value and its type
+    ///
+    pub fn parse_accesses(
+        input: &mut ParseInput <'code>,
+        ctx: &impl Context <'code>,
+        mut value: PrimitiveExpr <'code>,
+        mut ty: TypeIndex
+    ) -> Result <(PrimitiveExpr <'code>, TypeIndex)> {
+        loop {
+            let cur = input.get();
+
+            if input.dot().0.is_err() {
+                break
+            }
+
+            let name = input.ident_as_spanned_str()?;
+
+            if let Some(fun) = Self::resolve_method(input, ctx, &ty, name)? {
+                (value, ty) = CallExpr::parse_method(input, ctx, cur, name, fun, Expr::from_primitive(value, ty))?;
+                continue
+            }
+
+            let (field, field_ty) = Self::resolve(input, &ty, name)?;
+
+            let span = Span {
+                file: value.span().file,
+                start: value.span().start,
+                end: name.span.end
+            };
+
+            value = PrimitiveExpr::Field(Box::new(Self {
+                value,
+                name,
+                field,
+                ty: field_ty.clone(),
+                span
+            }));
+            ty = field_ty
+        }
+
+        Result(Ok((value, ty)))
+    }
+
+    ///
+    /// Finds the method named `name` of the type `ty`, returning `None` if there is no function with such name
+    ///
+    /// It is an error if the function is `statik` or private and called not from a function of the same type
+    ///
+    pub fn resolve_method <'a> (
+        input: &ParseInput <'code>,
+        ctx: &'a impl Context <'code>,
+        ty: &TypeIndex,
+        name: Spanned <&'code str>
+    ) -> Result <Option <(usize, &'a Fn <'code>)>> {
+        let baked = match &input.type_bases {
+            TypeBaseContainer::Baked(baked) => baked,
+            _ => unreachable!()
+        };
+
+        // The methods of the instances are the ones of the generic types
+        let owner = match ty.base() {
+            Some((idx, _)) => match &baked[idx as usize].instance_of {
+                Some((generic, _)) => *generic,
+                None => idx
+            },
+            None => return Result(Ok(None))
+        };
+
+        let (idx, fun) = match ctx.functions().find(|(_, fun)| fun.name == name && fun.owner == Some(owner)) {
+            Some(x) => x,
+            None => return Result(Ok(None))
+        };
+
+        let type_name = baked[owner as usize].name.data;
+
+        let error = |message: String, clarifying: String| Result(Err(Error {
+            span: name.span,
+            message,
+            clarifying
+        }));
+
+        if !fun.is_method {
+            return error(
+                format!("the function `{type_name}::{}` is not a method", name.data),
+                format!("help: call it as `{type_name}::{}`", name.data)
+            )
+        }
+
+        if fun.visibility == Visibility::Private && ctx.owner() != Some(owner) {
+            return error(
+                format!("the function `{type_name}::{}` is private", name.data),
+                format!("it can be called only by the functions of `{type_name}`")
+            )
+        }
+
+        Result(Ok(Some((idx, fun))))
+    }
+
+    /// Finds the field named `name` in the type `ty`, returning its index and type
+    fn resolve(input: &mut ParseInput <'code>, ty: &TypeIndex, name: Spanned <&'code str>) -> Result <(usize, TypeIndex)> {
+        let error = |message: String, clarifying: String| Result(Err(Error {
+            span: name.span,
+            message,
+            clarifying
+        }));
+
+        let baked = match &input.type_bases {
+            TypeBaseContainer::Baked(baked) => baked,
+            _ => unreachable!()
+        };
+
+        let (base, args) = match ty.base() {
+            Some((idx, args)) => (&baked[idx as usize], args.to_vec()),
+            None => return error(format!("no field or method `{}` in the type `{:?}`", name.data, ty.debug(input)), String::from("only the values of `ty` types have fields"))
+        };
+
+        let fields = match &base.kind {
+            BakedTypeBaseKind::TypeProduct(fields) => fields,
+            _ => return error(format!("no field or method `{}` in the type `{:?}`", name.data, ty.debug(input)), String::from("only the values of `ty` types have fields"))
+        };
+
+        let field = match fields.vec.iter().position(|field| field.name.data == name.data) {
+            Some(field) => field,
+            None => return error(
+                format!("no field or method `{}` in the type `{:?}`", name.data, ty.debug(input)),
+                match fields.vec.len() {
+                    0 => String::from("the type has no fields"),
+                    _ => format!("the fields are {}", fields.vec.iter().map(|field| format!("`{}`", field.name.data)).collect::<Vec <_>>().join(", "))
+                }
+            )
+        };
+
+        let field_ty = fields.vec[field].ty.clone();
+        let params = base.params.clone();
+
+        // The fields of a generic type applied to the type parameters of the function being checked
+        Result(Ok((field, field_ty.substitute(input, &params, &args))))
+    }
+}
@@ -0,0 +1,164 @@
+use core::fmt::{Formatter, Result as FmtResult};
+use crate::*;
+
+#[derive(Clone)]
+pub struct IfBranch <'code> {
+    pub condition: Expr <'code>,
+    pub body: Block <'code>
+}
+
+impl <'code> ParseDebug for IfBranch <'code> {
+    fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
+        f.debug_struct("IfBranch")
+            .field("condition", &self.condition.debug(input))
+            .field("body", &self.body.debug(input))
+            .finish()
+    }
+}
+
+///
+/// `if <cond> then <expr>`, `if <cond>` followed by an indented block,
+/// optionally continued by `else if ...` and `else ...`
+///
+#[derive(Clone)]
+pub struct IfExpr <'code> {
+    pub branches: Vec <IfBranch <'code>>,
+    pub otherwise: Option <Block <'code>>,
+    pub span: Span
+}
+
+impl <'code> GetSpan for IfExpr <'code> {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl <'code> ParseDebug for IfExpr <'code> {
+    fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
+        let mut builder = f.debug_struct("IfExpr");
+
+        for branch in &self.branches {
+            builder.field("if", &branch.debug(input));
+        }
+
+        if let Some(otherwise) = &self.otherwise {
+            builder.field("else", &otherwise.debug(input));
+        }
+
+        builder.finish()
+    }
+}
+
+impl <'code> IfExpr <'code> {
+    pub fn parse(input: &mut ParseInput <'code>, ctx: &impl Context <'code>) -> Result <(Self, TypeIndex)> {
+
+        let mut keyword = input.keyword("if")?;
+        let start = keyword.start;
+
+        let mut branches = vec![];
+        let mut branches_types = vec![];
+        let mut otherwise = None;
+
+        loop {
+            let condition = Expr::parse(input, ctx)?;
+
+            let bool = TypeIndex::builtin(input, "bool");
+            if condition.ty != bool {
+                return Result(Err(Error {
+                    span: condition.span(),
+                    message: String::from("type mismatch: the condition should have `bool` type"),
+                    clarifying: format!("expected `bool`, got `{:?}`", condition.ty.debug(input))
+                }))
+            }
+
+            // `then` is required only for inline branches
+            if let Result(Err(err)) = input.keyword("then") {
+                if input.is_exhausted() || !check_if_the_next_token_is_newline(input) {
+                    return Result(Err(err))
+                }
+            }
+
+            let (body, ty) = Block::parse_inline_or_indented(input, ctx, keyword)?;
+
+            branches.push(IfBranch {
+                condition,
+                body
+            });
+            branches_types.push(ty);
+
+            keyword = match Self::parse_else(input) {
+                Some(span) => span,
+                None => break
+            };
+
+            if !input.is_exhausted() && check_if_the_next_token_is_keyword(input, &["if"]) {
+                keyword = input.keyword("if")?;
+                continue
+            }
+
+            let (body, ty) = Block::parse_inline_or_indented(input, ctx, keyword)?;
+
+            otherwise = Some(body);
+            branches_types.push(ty);
+
+            break
+        }
+
+        let bodies = branches.iter().map(|x| &x.body).chain(otherwise.iter());
+
+        // Check all the branches have the same type or `()` if there is no `else`
+        let ty = if otherwise.is_some() {
+            branches_types[0].clone()
+        } else {
+            TypeIndex::UNIT_TUPLE
+        };
+        for (body, body_ty) in bodies.clone().zip(&branches_types) {
+            if *body_ty != ty {
+                return Result(Err(Error {
+                    span: body.span(),
+                    message: if otherwise.is_some() {
+                        String::from("type mismatch: all the branches of `if` should have the same type")
+                    } else {
+                        String::from("type mismatch: `if` without `else` should have `()` type")
+                    },
+                    clarifying: format!("expected `{:?}`, got `{:?}`", ty.debug(input), body_ty.debug(input))
+                }))
+            }
+        }
+
+        // There is always at least one branch
+        let end = bodies.last().unwrap().span().end;
+
+        Result(Ok((Self {
+            branches,
+            otherwise,
+            span: Span {
+                file: input.file(),
+                start,
+                end
+            }
+        }, ty)))
+    }
+
+    ///
+    /// Tries to find `else` either right after the branch on the same line
+    /// or in the beginning of the next line with the same indentation as the `if` has,
+    /// which is right after the indented branch
+    ///
+    /// Returns the span of `else` on success, leaves `input` untouched otherwise
+    ///
+    fn parse_else(input: &mut ParseInput <'code>) -> Option <Span> {
+        let cur = input.get();
+
+        if !input.is_exhausted() && check_if_the_next_token_is_newline(input) {
+            input.go_forward()
+        }
+
+        if !input.is_exhausted() && check_if_the_next_token_is_keyword(input, &["else"]) {
+            return input.keyword("else").0.ok()
+        }
+
+        input.set(cur);
+        None
+    }
+}
@@ -0,0 +1,194 @@
+use core::fmt::{Formatter, Result as FmtResult};
+use crate::*;
+
+#[derive(Clone)]
+pub struct MatchArm <'code> {
+    pub pattern: Pattern <'code>,
+    pub body: Block <'code>
+}
+
+impl <'code> ParseDebug for MatchArm <'code> {
+    fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
+        f.debug_struct("MatchArm")
+            .field("pattern", &self.pattern.debug(input))
+            .field("body", &self.body.debug(input))
+            .finish()
+    }
+}
+
+///
+/// `match <value>` followed by the indented arms, each one is `<pattern> => <body>`,
+/// where the body is either an expression on the same line or an indented block
+///
+/// The value is compared with the patterns from top to bottom, the body of the first matching arm is evaluated
+///
+#[derive(Clone)]
+pub struct MatchExpr <'code> {
+    pub value: Expr <'code>,
+    pub arms: Vec <MatchArm <'code>>,
+    pub span: Span
+}
+
+impl <'code> GetSpan for MatchExpr <'code> {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl <'code> ParseDebug for MatchExpr <'code> {
+    fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
+        let mut builder = f.debug_struct("MatchExpr");
+
+        builder.field("value", &self.value.debug(input));
+
+        for arm in &self.arms {
+            builder.field("arm", &arm.debug(input));
+        }
+
+        builder.finish()
+    }
+}
+
+impl <'code> MatchExpr <'code> {
+    pub fn parse(input: &mut ParseInput <'code>, ctx: &impl Context <'code>) -> Result <(Self, TypeIndex)> {
+        let keyword = input.keyword("match")?;
+
+        let value = Expr::parse(input, ctx)?;
+
+        input.newline()?;
+
+        let block = input.find_end_of_block_and_return_everything_in_it_and_also_go_forward_to_its_end();
+
+        let old_token_stream = core::mem::replace(&mut input.stream, TokenStream::from(block));
+
+        let parsed = Self::parse_arms(input, ctx, &value.ty);
+
+        input.stream = old_token_stream;
+
+        let (arms, types) = parsed?;
+
+        let last = match arms.last() {
+            Some(last) => last,
+            None => return Result(Err(Error {
+                span: keyword,
+                message: String::from("`match` should have at least one arm"),
+                clarifying: String::from("help: add the arms below, indented by one more level, like `<pattern> => <expression>`")
+            }))
+        };
+
+        let span = Span {
+            file: keyword.file,
+            start: keyword.start,
+            end: last.body.span().end
+        };
+
+        // Check all the arms have the same type
+        let ty = types[0].clone();
+        for (arm, arm_ty) in arms.iter().zip(&types) {
+            if *arm_ty != ty {
+                return Result(Err(Error {
+                    span: arm.body.span(),
+                    message: String::from("type mismatch: all the arms of `match` should have the same type"),
+                    clarifying: format!("expected `{:?}`, got `{:?}`", ty.debug(input), arm_ty.debug(input))
+                }))
+            }
+        }
+
+        let usefulness = Usefulness::check(input, &value.ty, &arms.iter().map(|arm| &arm.pattern).collect::<Vec <_>>());
+
+        if !usefulness.missing.is_empty() {
+            let patterns = usefulness.missing.iter().map(|pattern| format!("`{pattern}`")).collect::<Vec <_>>().join(", ");
+
+            return Result(Err(Error {
+                span: value.span(),
+                message: format!(
+                    "non-exhaustive patterns: {patterns}{} not covered",
+                    if usefulness.more_missing { " and more" } else { "" }
+                ),
+                clarifying: String::from("help: add the arms for these values or the one with `_`")
+            }))
+        }
+
+        Result(Ok((Self {
+            value,
+            arms,
+            span
+        }, ty)))
+    }
+
+    ///
+    /// Parses the arms of the `match` over a value of the type `ty`,
+    /// everything left in the `input` stream is taken as the arms
+    ///
+    /// An arm that fails to parse is skipped together with the lines nested into it,
+    /// all the errors but the first one are reported, the first one is returned
+    ///
+    fn parse_arms(input: &mut ParseInput <'code>, ctx: &impl Context <'code>, ty: &TypeIndex) -> Result <(Vec <MatchArm <'code>>, Vec <TypeIndex>)> {
+        let mut arms = vec![];
+        let mut types = vec![];
+        let mut errors = vec![];
+
+        loop {
+            remove_newlines_and_docs(input);
+
+            if input.is_exhausted() {
+                break
+            }
+
+            let start = input.get();
+
+            let arm = (|| {
+                let mut variables = vec![];
+                let pattern = Pattern::parse(input, ctx, ty, &mut variables)?;
+
+                let arrow = input.fat_arrow()?.span;
+
+                let mut arm_ctx = ctx.enter_block();
+                arm_ctx.bind_variables(variables);
+
+                let is_inline = !input.is_exhausted() && input.stream.buf[input.get()].kind != TokenKind::Newline;
+
+                let (body, body_ty) = Block::parse_inline_or_indented(input, &arm_ctx, arrow)?;
+
+                // An indented body ends right at the next arm, an inline one -- at the end of the line
+                if is_inline && !input.is_exhausted() {
+                    input.newline()?;
+                }
+
+                Result(Ok((MatchArm {
+                    pattern,
+                    body
+                }, body_ty)))
+            })();
+
+            match arm.0 {
+                Ok((arm, body_ty)) => {
+                    arms.push(arm);
+                    types.push(body_ty)
+                },
+                Err(err) => {
+                    errors.push(err);
+                    input.set(start);
+                    input.skip_to_next_line()
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            let first = errors.remove(0);
+            for err in errors {
+                input.report(err)
+            }
+            return Result(Err(first))
+        }
+
+        Result(Ok((arms, types)))
+    }
+
+    /// Returns the arms that never match since the ones before them cover everything they do
+    pub fn unreachable_arms <'a> (&'a self, input: &ParseInput) -> impl Iterator <Item = &'a MatchArm <'code>> {
+        let usefulness = Usefulness::check(input, &self.value.ty, &self.arms.iter().map(|arm| &arm.pattern).collect::<Vec <_>>());
+
+        usefulness.unreachable.into_iter().map(|idx| &self.arms[idx])
+    }
+}
@@ -1 +1 @@
-crate::modules!(primitive un_and_bin_ops call_expr define_expr);
+crate::modules!(primitive un_and_bin_ops call_expr try_expr define_expr block if_expr binding node variant_expr product_expr field_expr pattern exhaustiveness match_expr);
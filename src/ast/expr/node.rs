@@ -0,0 +1,48 @@
+use crate::*;
+
+///
+/// The uniform view over an expression of any precedence level,
+/// used by the passes that walk the baked expressions
+///
+pub enum ExprNode <'a, 'code> {
+    Unary {
+        op: UnaryOperator,
+        value: &'a dyn AsExprNode <'code>,
+        span: Span
+    },
+    Binary {
+        op: BinaryOperator,
+        left: &'a dyn AsExprNode <'code>,
+        right: &'a dyn AsExprNode <'code>,
+        span: Span
+    },
+    Call(&'a CallExprFull <'code>),
+    Try(&'a TryExprFull <'code>),
+    Primitive(&'a PrimitiveExpr <'code>)
+}
+
+pub trait AsExprNode <'code> {
+    fn node(&self) -> ExprNode <'_, 'code>;
+}
+
+impl <'code> AsExprNode <'code> for CallExprFull <'code> {
+    fn node(&self) -> ExprNode <'_, 'code> {
+        ExprNode::Call(self)
+    }
+}
+
+impl <'code> AsExprNode <'code> for TryExprFull <'code> {
+    fn node(&self) -> ExprNode <'_, 'code> {
+        ExprNode::Try(self)
+    }
+}
+
+impl <'code> AsExprNode <'code> for PrimitiveExpr <'code> {
+    fn node(&self) -> ExprNode <'_, 'code> {
+        match self {
+            // The method calls are the same as the calls of the functions
+            Self::MethodCall(call) => ExprNode::Call(call),
+            _ => ExprNode::Primitive(self)
+        }
+    }
+}
@@ -0,0 +1,435 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use crate::*;
+
+///
+/// The left side of an arm of `match` -- a shape the value is compared with
+///
+/// Examples: `_`, `x`, `42`, `-1`, `true`, `(a, _)`, `Some (Pair x 0)`, `Shape::Empty`, `wrapper a b x`
+///
+#[derive(Clone)]
+pub enum Pattern <'code> {
+    /// `_`, matches everything
+    Wildcard(Span),
+
+    /// A name, matches everything and binds the value to a variable
+    Binding(Spanned <&'code str>),
+
+    /// An integer literal, possibly negative
+    Int(Spanned <i128>),
+
+    Bool(Spanned <bool>),
+
+    /// `(a, b)`, `(a,)` or `()`
+    Tuple(Vec <Pattern <'code>>, Span),
+
+    /// A variant of an enum followed by the patterns for its payload
+    Variant {
+        /// The index of the enum in the type bases container
+        ty: u32,
+
+        /// The index of the variant in the enum
+        variant: usize,
+        args: Vec <Pattern <'code>>,
+        span: Span
+    },
+
+    /// A name of a `ty` type followed by the patterns for its fields in the order of their declaration
+    Product {
+        /// The index of the type in the type bases container
+        ty: u32,
+        fields: Vec <Pattern <'code>>,
+        span: Span
+    }
+}
+
+impl <'code> GetSpan for Pattern <'code> {
+    fn span(&self) -> Span {
+        match self {
+            Self::Wildcard(span) | Self::Tuple(_, span) => *span,
+            Self::Binding(name) => name.span,
+            Self::Int(int) => int.span,
+            Self::Bool(bool) => bool.span,
+            Self::Variant { span, .. } | Self::Product { span, .. } => *span
+        }
+    }
+}
+
+impl <'code> ParseDebug for Pattern <'code> {
+    fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
+        let baked = match &input.type_bases {
+            TypeBaseContainer::Baked(baked) => baked,
+            _ => unreachable!()
+        };
+
+        match self {
+            Self::Wildcard(_) => f.write_str("_"),
+            Self::Binding(name) => name.fmt(f),
+            Self::Int(int) => int.fmt(f),
+            Self::Bool(bool) => bool.fmt(f),
+            Self::Tuple(patterns, _) => {
+                let mut builder = f.debug_tuple("");
+                for pattern in patterns {
+                    builder.field(&pattern.debug(input));
+                }
+                builder.finish()
+            },
+            Self::Variant { ty, variant, args, .. } => {
+                let name = match &baked[*ty as usize].kind {
+                    BakedTypeBaseKind::TypeSum(variants) => variants.vec[*variant].name.data,
+                    _ => unreachable!()
+                };
+                let mut builder = f.debug_tuple(name);
+                for arg in args {
+                    builder.field(&arg.debug(input));
+                }
+                builder.finish()
+            },
+            Self::Product { ty, fields, .. } => {
+                let mut builder = f.debug_tuple(baked[*ty as usize].name.data);
+                for field in fields {
+                    builder.field(&field.debug(input));
+                }
+                builder.finish()
+            }
+        }
+    }
+}
+
+impl <'code> Pattern <'code> {
+    ///
+    /// Parses a pattern matching values of the type `ty`,
+    /// pushing the variables it binds to `variables`
+    ///
+    /// Variants and types take exactly as many patterns after them as they have payload types or fields,
+    /// so nested ones need no braces: `Some Some x` is the same as `Some (Some x)`
+    ///
+    pub fn parse(
+        input: &mut ParseInput <'code>,
+        ctx: &impl Context <'code>,
+        ty: &TypeIndex,
+        variables: &mut Vec <TypedVariable <'code>>
+    ) -> Result <Self> {
+        if let Ok(underscore) = input.underscore().0 {
+            return Result(Ok(Self::Wildcard(underscore.span)))
+        }
+
+        if let Ok(open) = input.open_brace().0 {
+            let start = open.span.start;
+            return Self::parse_braced(input, ctx, ty, variables, start)
+        }
+
+        if let Ok(minus) = input.minus().0 {
+            let minus = minus.span;
+            let literal = input.int_as_spanned_str()?;
+            let (int, int_ty) = IntExpr::from_literal(input, literal, Some(minus))?;
+            Self::check_type(input, int.span, ty, &int_ty)?;
+            return Result(Ok(Self::Int(Spanned {
+                data: int.value,
+                span: int.span
+            })))
+        }
+
+        if let Ok(literal) = input.int_as_spanned_str().0 {
+            let (int, int_ty) = IntExpr::from_literal(input, literal, None)?;
+            Self::check_type(input, int.span, ty, &int_ty)?;
+            return Result(Ok(Self::Int(Spanned {
+                data: int.value,
+                span: int.span
+            })))
+        }
+
+        let name = input.ident_as_spanned_str()?;
+
+        if name.data == "true" || name.data == "false" {
+            let bool = TypeIndex::builtin(input, "bool");
+            Self::check_type(input, name.span, ty, &bool)?;
+            return Result(Ok(Self::Bool(Spanned {
+                data: name.data == "true",
+                span: name.span
+            })))
+        }
+
+        // `module::Type` or `module::Enum::Variant`
+        let (module, name) = match (input.find_type(name.data), input.imported_module(name.data)) {
+            (None, Some(module)) if input.colon_colon().0.is_ok() => (Some(module), input.ident_as_spanned_str()?),
+            _ => (None, name)
+        };
+
+        let ty_idx = match module {
+            Some(module) => input.find_type_in(Some(module), name.data),
+            None => input.find_type(name.data)
+        };
+
+        // `Enum::Variant`
+        let variant = if input.colon_colon().0.is_ok() {
+            let variant = input.ident_as_spanned_str()?;
+            VariantExpr::resolve(input, ctx, Some((name, ty_idx)), variant)?
+        } else if module.is_some() {
+            None
+        } else {
+            // The variants of the enum being matched go first, so that they are never ambiguous
+            let own = match ty.base() {
+                Some((idx, _)) => match &Self::baked(input)[idx as usize].kind {
+                    BakedTypeBaseKind::TypeSum(variants) => variants.vec
+                        .iter()
+                        .position(|variant| variant.name.data == name.data)
+                        .map(|variant| (idx, variant)),
+                    _ => None
+                },
+                None => None
+            };
+
+            match own {
+                Some(own) => Some(own),
+                None => VariantExpr::resolve(input, ctx, None, name)?
+            }
+        };
+
+        if let Some((variant_ty, variant)) = variant {
+            let variant_ty = Self::own_base(input, ty, variant_ty);
+
+            Self::check_type(input, name.span, ty, &Self::type_of_base(ty, variant_ty))?;
+
+            let payload = match &Self::baked(input)[variant_ty as usize].kind {
+                BakedTypeBaseKind::TypeSum(variants) => variants.vec[variant].payload.clone(),
+                _ => unreachable!()
+            };
+            let payload = Self::substitute_type_args(input, ty, payload);
+
+            let args = Self::parse_args(input, ctx, name, &payload, variables)?;
+
+            return Result(Ok(Self::Variant {
+                ty: variant_ty,
+                variant,
+                span: Span {
+                    file: name.span.file,
+                    start: name.span.start,
+                    end: args.last().map(|x| x.span().end).unwrap_or(name.span.end)
+                },
+                args
+            }))
+        }
+
+        let product = ty_idx.filter(|idx| matches!(Self::baked(input)[*idx as usize].kind, BakedTypeBaseKind::TypeProduct(_)));
+
+        if let Some(product_ty) = product {
+            let product_ty = Self::own_base(input, ty, product_ty);
+
+            Self::check_type(input, name.span, ty, &Self::type_of_base(ty, product_ty))?;
+
+            let types = match &Self::baked(input)[product_ty as usize].kind {
+                BakedTypeBaseKind::TypeProduct(fields) => fields.vec.iter().map(|field| field.ty.clone()).collect(),
+                _ => unreachable!()
+            };
+            let types = Self::substitute_type_args(input, ty, types);
+
+            let fields = Self::parse_args(input, ctx, name, &types, variables)?;
+
+            return Result(Ok(Self::Product {
+                ty: product_ty,
+                span: Span {
+                    file: name.span.file,
+                    start: name.span.start,
+                    end: fields.last().map(|x| x.span().end).unwrap_or(name.span.end)
+                },
+                fields
+            }))
+        }
+
+        if module.is_some() {
+            return Result(Err(Error {
+                span: name.span,
+                message: format!("no `ty` type named `{}` in the module", name.data),
+                clarifying: String::from("here")
+            }))
+        }
+
+        if let Some(previous) = variables.iter().find(|v| v.name == name) {
+            return Result(Err(Error {
+                span: name.span,
+                message: format!("`{}` is bound more than once in the same pattern", name.data),
+                clarifying: format!("first bound at {:?}", previous.name.span.start)
+            }))
+        }
+
+        variables.push(TypedVariable {
+            name,
+            doc: Doc::EMPTY,
+            ty: ty.clone()
+        });
+
+        Result(Ok(Self::Binding(name)))
+    }
+
+    ///
+    /// Parses the rest of `(a, b)`, `(a,)`, `()` or just `(a)` after the opening brace
+    ///
+    fn parse_braced(
+        input: &mut ParseInput <'code>,
+        ctx: &impl Context <'code>,
+        ty: &TypeIndex,
+        variables: &mut Vec <TypedVariable <'code>>,
+        start: CursorPosition
+    ) -> Result <Self> {
+        let len = match Self::tuple_len(input) {
+            Some(len) => len,
+            None => {
+                let pattern = Self::parse(input, ctx, ty, variables)?;
+                input.close_brace()?;
+                return Result(Ok(pattern))
+            }
+        };
+
+        let types = match ty {
+            TypeIndex::Tuple(types) if types.len() == len => types,
+            _ => return Result(Err(Error {
+                span: Span::extend_by_one(input.file(), start),
+                message: format!("type mismatch: a tuple pattern of {len} elements cannot match `{:?}`", ty.debug(input)),
+                clarifying: match ty {
+                    TypeIndex::Tuple(types) => format!("expected {} elements, got {len}", types.len()),
+                    _ => String::from("the value is not a tuple")
+                }
+            }))
+        };
+
+        let mut patterns = vec![];
+
+        let end = loop {
+            if let Result(Ok(t)) = input.close_brace() {
+                break t.span.end
+            }
+
+            patterns.push(Self::parse(input, ctx, &types[patterns.len()], variables)?);
+
+            if let Result(Ok(t)) = input.close_brace() {
+                break t.span.end
+            }
+
+            input.comma()?;
+        };
+
+        Result(Ok(Self::Tuple(patterns, Span {
+            file: input.file(),
+            start,
+            end
+        })))
+    }
+
+    ///
+    /// Returns the number of elements if the braces opened right before contain a tuple pattern,
+    /// i.e. are empty or have a comma on their own level, `None` if they just wrap a single pattern
+    ///
+    fn tuple_len(input: &ParseInput <'code>) -> Option <usize> {
+        let mut depth = 0usize;
+        let mut commas = 0;
+        let mut trailing_comma = false;
+        let mut is_empty = true;
+
+        for token in &input.stream.buf[input.get()..] {
+            match token.kind {
+                TokenKind::CloseBrace if depth == 0 => break,
+                TokenKind::Newline => break,
+                TokenKind::OpenBrace => depth += 1,
+                TokenKind::CloseBrace => depth -= 1,
+                TokenKind::Comma if depth == 0 => {
+                    commas += 1;
+                    trailing_comma = true;
+                    continue
+                },
+                _ => ()
+            }
+
+            trailing_comma = false;
+            is_empty = false
+        }
+
+        if is_empty {
+            Some(0)
+        } else if commas == 0 {
+            None
+        } else {
+            Some(commas + !trailing_comma as usize)
+        }
+    }
+
+    /// Parses exactly one pattern for each of `expected` types after `name`
+    fn parse_args(
+        input: &mut ParseInput <'code>,
+        ctx: &impl Context <'code>,
+        name: Spanned <&'code str>,
+        expected: &[TypeIndex],
+        variables: &mut Vec <TypedVariable <'code>>
+    ) -> Result <Vec <Self>> {
+        let mut args = vec![];
+
+        for ty in expected {
+            let is_end = input.is_exhausted() || matches!(
+                input.stream.buf[input.get()].kind,
+                TokenKind::Newline | TokenKind::FatArrow | TokenKind::Comma | TokenKind::CloseBrace
+            );
+
+            if is_end {
+                return Result(Err(Error {
+                    span: name.span,
+                    message: format!("wrong number of patterns after `{}`", name.data),
+                    clarifying: format!("expected `{}`, got `{}`", expected.len(), args.len())
+                }))
+            }
+
+            args.push(Self::parse(input, ctx, ty, variables)?)
+        }
+
+        Result(Ok(args))
+    }
+
+    ///
+    /// Returns the index of the type base of `ty` if it is the instance of the generic type `base`
+    /// or `base` itself, otherwise returns `base`
+    ///
+    fn own_base(input: &ParseInput <'code>, ty: &TypeIndex, base: u32) -> u32 {
+        match ty.base() {
+            Some((idx, _)) if idx == base => idx,
+            Some((idx, _)) if matches!(&Self::baked(input)[idx as usize].instance_of, Some((generic, _)) if *generic == base) => idx,
+            _ => base
+        }
+    }
+
+    /// Returns the type of the values the patterns of the type base `base` match, when the value matched is of the type `ty`
+    fn type_of_base(ty: &TypeIndex, base: u32) -> TypeIndex {
+        match ty.base() {
+            Some((idx, _)) if idx == base => ty.clone(),
+            _ => TypeIndex::Scalar(base)
+        }
+    }
+
+    /// Replaces the type parameters in `types` taken from the definition of the type of `ty` with its type arguments
+    fn substitute_type_args(input: &mut ParseInput <'code>, ty: &TypeIndex, types: Vec <TypeIndex>) -> Vec <TypeIndex> {
+        let (idx, args) = match ty.base() {
+            Some((idx, args)) if !args.is_empty() => (idx, args),
+            _ => return types
+        };
+
+        let params = Self::baked(input)[idx as usize].params.clone();
+
+        types.iter().map(|x| x.substitute(input, &params, args)).collect()
+    }
+
+    fn baked <'a> (input: &'a ParseInput <'code>) -> &'a [BakedTypeBase <'code>] {
+        match &input.type_bases {
+            TypeBaseContainer::Baked(baked) => baked,
+            _ => unreachable!()
+        }
+    }
+
+    fn check_type(input: &ParseInput, span: Span, expected: &TypeIndex, got: &TypeIndex) -> Result <()> {
+        if expected == got {
+            return Result(Ok(()))
+        }
+
+        Result(Err(Error {
+            span,
+            message: String::from("type mismatch: the pattern cannot match the value"),
+            clarifying: format!("expected `{:?}`, got `{:?}`", expected.debug(input), got.debug(input))
+        }))
+    }
+}
@@ -1,9 +1,11 @@
 use core::fmt::{Formatter, Result as FmtResult};
+use core::num::IntErrorKind;
 use crate::*;
 
 #[derive(Clone)]
 pub struct BracedExpr <'code> {
-    pub value: LastExpr <'code>
+    pub value: OpExpr <'code>,
+    pub span: Span
 }
 
 impl <'code> BracedExpr <'code> {
@@ -11,11 +13,16 @@ impl <'code> BracedExpr <'code> {
         let cur = input.get();
 
         let res = (|| {
-            input.open_brace()?;
-            let Expr { value, ty } = Expr::parse(input, &ctx.set_not_primary())?;
-            input.close_brace()?;
+            let start = input.open_brace()?.span.start;
+            let Expr { value, ty } = Expr::parse(input, &ctx.enter_braces())?;
+            let end = input.close_brace()?.span.end;
             Result(Ok((Self {
-                value
+                value,
+                span: Span {
+                    file: input.file(),
+                    start,
+                    end
+                }
             }, ty)))
         })();
 
@@ -60,6 +67,7 @@ impl <'code> TupleExpr <'code> {
         Result(Ok((Self {
             value,
             span: Span {
+                file: input.file(),
                 start,
                 end
             }
@@ -67,19 +75,106 @@ impl <'code> TupleExpr <'code> {
     }
 }
 
+#[derive(Clone)]
+pub struct IntExpr {
+    /// Negative for literals with a minus before them, which are folded into one
+    pub value: i128,
+    pub span: Span
+}
+
+impl IntExpr {
+    /// The type every integer literal has
+    pub const TYPE: &'static str = "i32";
+
+    ///
+    /// Checks the digits of the `literal` and whether its value fits into [`IntExpr::TYPE`]
+    ///
+    /// `minus` is the span of the minus right before the literal, if it is negated,
+    /// which lets the literal be the minimum of the type
+    ///
+    pub fn from_literal(input: &mut ParseInput, literal: Spanned <&str>, minus: Option <Span>) -> Result <(Self, TypeIndex)> {
+        let (radix, digits) = if let Some(digits) = literal.data.strip_prefix("0x") {
+            (16, digits)
+        } else if let Some(digits) = literal.data.strip_prefix("0b") {
+            (2, digits)
+        } else {
+            (10, literal.data)
+        };
+
+        let digits = digits.replace('_', "");
+
+        let span = match minus {
+            Some(minus) => Span {
+                file: literal.span.file,
+                start: minus.start,
+                end: literal.span.end
+            },
+            None => literal.span
+        };
+        let sign = if minus.is_some() { "-" } else { "" };
+
+        let error = |message: String, clarifying: String| Result(Err(Error {
+            span,
+            message,
+            clarifying
+        }));
+
+        if digits.is_empty() {
+            return error(format!("integer literal `{}` has no digits", literal.data), String::from("expected digits after the prefix"))
+        }
+
+        let value = match u128::from_str_radix(&digits, radix) {
+            Ok(ok) => ok,
+            Err(err) if *err.kind() == IntErrorKind::PosOverflow => u128::MAX,
+            Err(_) => return error(format!("invalid digit in the integer literal `{}`", literal.data), format!("only base {radix} digits are allowed"))
+        };
+
+        let max = if minus.is_some() { i32::MIN.unsigned_abs() as u128 } else { i32::MAX as u128 };
+
+        if value > max {
+            return error(format!("integer literal is out of range for `{}`", Self::TYPE), format!("`{sign}{}` does not fit into `{}`", literal.data, Self::TYPE))
+        }
+
+        let ty = TypeIndex::builtin(input, Self::TYPE);
+
+        Result(Ok((Self {
+            value: if minus.is_some() { -(value as i128) } else { value as i128 },
+            span
+        }, ty)))
+    }
+}
+
 #[derive(Clone)]
 pub enum PrimitiveExpr <'code> {
     Ident(Spanned <&'code str>),
+    Int(IntExpr),
+    Bool(Spanned <bool>),
+    If(Box <IfExpr <'code>>),
+    Match(Box <MatchExpr <'code>>),
     Braced(Box <BracedExpr <'code>>),
-    Tuple(Box <TupleExpr <'code>>)
+    Tuple(Box <TupleExpr <'code>>),
+    Variant(Box <VariantExpr <'code>>),
+    Product(Box <ProductExpr <'code>>),
+    Field(Box <FieldExpr <'code>>),
+
+    /// `value.method`, the value is the first argument
+    MethodCall(Box <CallExprFull <'code>>)
 }
 
 impl <'code> GetSpan for PrimitiveExpr <'code> {
     fn span(&self) -> Span {
         match self {
             Self::Ident(ident) => ident.span,
-            Self::Braced(braced) => braced.value.span(),
-            Self::Tuple(tuple) => tuple.span
+            Self::Int(int) => int.span,
+            Self::Bool(bool) => bool.span,
+            Self::If(if_expr) => if_expr.span,
+            Self::Match(match_expr) => match_expr.span,
+            Self::Braced(braced) => braced.span,
+            Self::Tuple(tuple) => tuple.span,
+            Self::Variant(variant) => variant.span,
+            Self::Product(product) => product.span,
+            Self::Field(field) => field.span,
+            Self::MethodCall(call) => call.span
         }
     }
 }
@@ -91,6 +186,14 @@ impl <'code> ParseDebug for PrimitiveExpr <'code> {
             Self::Ident(ident) => f.debug_tuple("Ident")
                 .field(ident)
                 .finish(),
+            Self::Int(int) => f.debug_tuple("Int")
+                .field(&int.value)
+                .finish(),
+            Self::Bool(bool) => f.debug_tuple("Bool")
+                .field(bool)
+                .finish(),
+            Self::If(if_expr) => if_expr.debug_impl(input, f),
+            Self::Match(match_expr) => match_expr.debug_impl(input, f),
             Self::Braced(expr) => f.debug_tuple("Braced")
                 .field(&expr.value.debug(input))
                 .finish(),
@@ -100,13 +203,37 @@ impl <'code> ParseDebug for PrimitiveExpr <'code> {
                     builder.field(&expr.debug(input));
                 }
                 builder.finish()
-            }
+            },
+            Self::Variant(variant) => variant.debug_impl(input, f),
+            Self::Product(product) => product.debug_impl(input, f),
+            Self::Field(field) => field.debug_impl(input, f),
+            Self::MethodCall(call) => call.debug_impl(input, f)
         }
     }
 }
 
 impl <'code> PrimitiveExpr <'code> {
+    /// The keywords that start a primitive expression and so cannot be names of functions or variables
+    pub const KEYWORDS: &'static [&'static str] = &["if", "match"];
+
     pub fn parse(input: &mut ParseInput <'code>, ctx: &impl Context <'code>) -> Result <(Self, TypeIndex)> {
+        let (value, ty) = Self::parse_operand(input, ctx)?;
+
+        FieldExpr::parse_accesses(input, ctx, value, ty)
+    }
+
+    /// Parses the expression without the accesses to its fields
+    fn parse_operand(input: &mut ParseInput <'code>, ctx: &impl Context <'code>) -> Result <(Self, TypeIndex)> {
+        if !input.is_exhausted() && check_if_the_next_token_is_keyword(input, &["if"]) {
+            let (expr, ty) = IfExpr::parse(input, ctx)?;
+            return Result(Ok((Self::If(Box::new(expr)), ty)))
+        }
+
+        if !input.is_exhausted() && check_if_the_next_token_is_keyword(input, &["match"]) {
+            let (expr, ty) = MatchExpr::parse(input, ctx)?;
+            return Result(Ok((Self::Match(Box::new(expr)), ty)))
+        }
+
         macro_rules! parse {
             ($input:ident, $( $name:ident => $expr:expr => { $($tt:tt)* } )*) => {
                 $(
@@ -130,19 +257,27 @@ impl <'code> PrimitiveExpr <'code> {
             input,
 
             ident => input.ident_as_spanned_str() => {
-                if let Some(var) = ctx.variables().find(|v| v.name == ok) {
+                if ok.data == "true" || ok.data == "false" {
+                    (Self::Bool(Spanned {
+                        data: ok.data == "true",
+                        span: ok.span
+                    }), TypeIndex::builtin(input, "bool"))
+                } else if let Some(var) = ctx.variables().find(|v| v.name == ok) {
                     (Self::Ident(ok), var.ty.clone())
                 } else {
                     return Result(Err(Error {
                         span: ok.span,
                         message: format!("no variable named `{}` found", ok.data),
-                        clarifying: String::from("here"),
-                        filename: input.filename.to_string(),
-                        code: input.code.to_string()
+                        clarifying: String::from("here")
                     }))
                 }
             }
 
+            int => input.int_as_spanned_str() => {
+                let (int, ty) = IntExpr::from_literal(input, ok, None)?;
+                (Self::Int(int), ty)
+            }
+
             braced => BracedExpr::parse(input, ctx) => {
                 (Self::Braced(Box::new(ok.0)), ok.1)
             }
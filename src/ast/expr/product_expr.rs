@@ -0,0 +1,70 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use crate::*;
+
+///
+/// Creation of a value of a `ty` type from the values of all its fields,
+/// it is the body of the generated `new` of the type
+///
+#[derive(Clone)]
+pub struct ProductExpr <'code> {
+    /// The index of the type in the type bases container
+    pub ty: u32,
+    pub span: Span,
+
+    /// The values of the fields in the order of their declaration
+    pub args: Vec <Expr <'code>>
+}
+
+impl <'code> GetSpan for ProductExpr <'code> {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl <'code> ParseDebug for ProductExpr <'code> {
+    fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
+        struct Inner <'code, 'a> {
+            input: &'a ParseInput <'code>,
+            args: &'a [Expr <'code>]
+        }
+
+        impl <'code, 'a> Debug for Inner <'code, 'a> {
+            fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+                f.debug_list()
+                    .entries(self.args.iter().map(|x| x.debug(self.input)))
+                    .finish()
+            }
+        }
+
+        f.debug_struct("ProductExpr")
+            .field("ty", &TypeIndex::Scalar(self.ty).debug(input))
+            .field("args", &Inner { input, args: &self.args })
+            .finish()
+    }
+}
+
+impl <'code> ProductExpr <'code> {
+    ///
+    /// Returns the body of the `new` of the `ty` type with the index `ty`, which is the function `fun`:
+    /// the value of the type created from the arguments of the function
+    ///
+    pub fn new_body(fun: &Fn <'code>, ty: u32) -> BakedFnBodyBase <'code> {
+        // The instances of `new` of a generic type create the values of the instances of the type
+        let ty = match fun.ret_ty {
+            TypeIndex::Scalar(instance) => instance,
+            _ => ty
+        };
+
+        let args = fun.args.iter().map(|arg| Expr::from_primitive(PrimitiveExpr::Ident(arg.name), arg.ty.clone())).collect();
+
+        let value = PrimitiveExpr::Product(Box::new(Self {
+            ty,
+            span: fun.name.span,
+            args
+        }));
+
+        BakedFnBodyBase {
+            body: vec![BakedFnBodyStmt::Expr(Expr::from_primitive(value, fun.ret_ty.clone()))]
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use core::fmt::{Formatter, Result as FmtResult};
+use crate::*;
+
+///
+/// The `?` operator -- `value?`, it applies to the whole call before it: `f x ?` is `(f x)?`
+///
+/// The type of the value should have the method `__try`, which returns `TryFork <Target, Error>`,
+/// the user-defined enum with the variants `Continue Target` and `Break Error`.
+/// On `Continue` the expression is its payload, on `Break` the function returns the payload at once
+///
+#[derive(Clone)]
+pub struct TryExprFull <'code> {
+    pub value: CallExpr <'code>,
+
+    /// The index of the `__try` method of the value in `ParseInput.top_level_items`
+    pub fun: usize,
+
+    /// The type of the payload of `Continue`, which is the type of the expression
+    pub ty: TypeIndex,
+    pub operator: Span
+}
+
+impl <'code> TryExprFull <'code> {
+    /// The name of the method `?` calls
+    pub const METHOD: &'static str = "__try";
+
+    /// The name of the enum the method returns
+    pub const FORK: &'static str = "TryFork";
+
+    /// The index of the `Continue` variant in `TryFork`
+    pub const CONTINUE: usize = 0;
+
+    /// The index of the `Break` variant in `TryFork`
+    pub const BREAK: usize = 1;
+}
+
+impl <'code> GetSpan for TryExprFull <'code> {
+    fn span(&self) -> Span {
+        Span {
+            file: self.value.span().file,
+            start: self.value.span().start,
+            end: self.operator.end
+        }
+    }
+}
+
+impl <'code> ParseDebug for TryExprFull <'code> {
+    fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
+        f.debug_struct("TryExpr")
+            .field("value", &self.value.debug(input))
+            .finish()
+    }
+}
+
+define_expr!(TryExpr = TryExprFull <'code>, CallExpr <'code>);
+
+impl <'code> TryExpr <'code> {
+    pub fn parse(input: &mut ParseInput <'code>, ctx: &impl Context <'code>) -> Result <(Self, TypeIndex)> {
+        let (value, ty) = CallExpr::parse(input, ctx)?;
+
+        // `?` after an argument is for the call
+        if ctx.is_argument() {
+            return Result(Ok((Self::Partial(Box::new(value)), ty)))
+        }
+
+        let operator = match input.question().0 {
+            Ok(question) => question.span,
+            Err(_) => return Result(Ok((Self::Partial(Box::new(value)), ty)))
+        };
+
+        let name = Spanned {
+            data: TryExprFull::METHOD,
+            span: operator
+        };
+
+        let error = |message: String, clarifying: String| Result(Err(Error {
+            span: operator,
+            message,
+            clarifying
+        }));
+
+        let (fun_idx, fun) = match FieldExpr::resolve_method(input, ctx, &ty, name)? {
+            Some(x) => x,
+            None => return error(
+                format!("the `?` operator cannot be applied to the type `{:?}`", ty.debug(input)),
+                format!("help: add the method `{}` returning `{} <Target, Error>` to the type", TryExprFull::METHOD, TryExprFull::FORK)
+            )
+        };
+
+        if fun.args.len() != 1 {
+            return error(
+                format!("the method `{}` used by `?` should take no arguments", TryExprFull::METHOD),
+                format!("it takes `{}`", fun.args.len() - 1)
+            )
+        }
+
+        let (target, break_ty) = match Self::fork_args(input, &fun.ret_ty) {
+            Some(x) => x,
+            None => return error(
+                format!("the method `{}` used by `?` should return `{} <Target, Error>`", TryExprFull::METHOD, TryExprFull::FORK),
+                format!(
+                    "it returns `{:?}`, and `{}` should be an enum with the variants `Continue Target` and `Break Error`",
+                    fun.ret_ty.debug(input),
+                    TryExprFull::FORK
+                )
+            )
+        };
+
+        let mut inferred = vec![None; fun.type_params.len()];
+
+        // The method is found by the type of the value, so it always fits
+        let fits = fun.args[0].ty.infer(input, &ty, &fun.type_params, &mut inferred);
+        debug_assert!(fits);
+
+        // The error is returned from the function, so the types are the same
+        let ret_ty = ctx.return_type();
+
+        if !break_ty.infer(input, ret_ty, &fun.type_params, &mut inferred) {
+            let known = fun.type_params
+                .iter()
+                .zip(&inferred)
+                .map(|(param, ty)| ty.clone().unwrap_or(TypeIndex::Generic(*param)))
+                .collect::<Vec <_>>();
+
+            return error(
+                String::from("type mismatch: `?` returns the error from the function"),
+                format!("the error is `{:?}`, but the function returns `{:?}`", break_ty.replace_params(&fun.type_params, &known).debug(input), ret_ty.debug(input))
+            )
+        }
+
+        let type_args = CallExpr::inferred_type_args(input, name, &fun.type_params, inferred)?;
+
+        let target = target.substitute(input, &fun.type_params, &type_args);
+
+        let fun = CallExpr::instantiate(input, name, (fun_idx, fun), type_args)?;
+
+        Result(Ok((Self::Full(Box::new(TryExprFull {
+            value,
+            fun,
+            ty: target.clone(),
+            operator
+        })), target)))
+    }
+
+    ///
+    /// Returns the types of the payloads of `Continue` and `Break` if `ty` is `TryFork`
+    /// and `TryFork` is defined as expected, `None` otherwise
+    ///
+    fn fork_args(input: &ParseInput <'code>, ty: &TypeIndex) -> Option <(TypeIndex, TypeIndex)> {
+        let baked = match &input.type_bases {
+            TypeBaseContainer::Baked(baked) => baked,
+            _ => unreachable!()
+        };
+
+        let (base, args) = match ty {
+            TypeIndex::Applied(base, args) => (*base, &args[..]),
+            TypeIndex::Scalar(idx) => match &baked[*idx as usize].instance_of {
+                Some((base, args)) => (*base, &args[..]),
+                None => return None
+            },
+            _ => return None
+        };
+
+        let fork = &baked[base as usize];
+
+        let variants = match &fork.kind {
+            BakedTypeBaseKind::TypeSum(variants) if fork.name.data == TryExprFull::FORK && fork.params.len() == 2 => &variants.vec,
+            _ => return None
+        };
+
+        let expected = [("Continue", fork.params[0]), ("Break", fork.params[1])];
+
+        let is_fork = variants.len() == expected.len() && variants
+            .iter()
+            .zip(expected)
+            .all(|(variant, (name, param))| variant.name.data == name && variant.payload == [TypeIndex::Generic(param)]);
+
+        match is_fork {
+            true => Some((args[TryExprFull::CONTINUE].clone(), args[TryExprFull::BREAK].clone())),
+            false => None
+        }
+    }
+}
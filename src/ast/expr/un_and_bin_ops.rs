@@ -4,12 +4,24 @@ use crate::*;
 #[derive(Clone)]
 pub struct UnOp <T> {
     pub value: T,
+    pub op: UnaryOperator,
     pub operator: Span
 }
 
+impl <'code, T: AsExprNode <'code>> AsExprNode <'code> for UnOp <T> {
+    fn node(&self) -> ExprNode <'_, 'code> {
+        ExprNode::Unary {
+            op: self.op,
+            value: &self.value,
+            span: self.operator
+        }
+    }
+}
+
 impl <T: GetSpan> GetSpan for UnOp <T> {
     fn span(&self) -> Span {
         Span {
+            file: self.operator.file,
             start: self.operator.start,
             end: self.value.span().end
         }
@@ -20,7 +32,7 @@ impl <T: ParseDebug> ParseDebug for UnOp <T> {
     fn debug_impl(&self, input: &ParseInput, f: &mut Formatter<'_>) -> FmtResult {
         f.debug_struct("UnnOp")
             .field("left", &self.value.debug(input))
-            .field("operator", &self.operator.get_spanned_lines(&input.code)[0])
+            .field("operator", &self.operator.get_spanned_lines(input.source_map.get(self.operator.file).unwrap())[0])
             .finish()
     }
 }
@@ -29,12 +41,25 @@ impl <T: ParseDebug> ParseDebug for UnOp <T> {
 pub struct BinOp <T> {
     pub left: T,
     pub right: T,
+    pub op: BinaryOperator,
     pub operator: Span
 }
 
+impl <'code, T: AsExprNode <'code>> AsExprNode <'code> for BinOp <T> {
+    fn node(&self) -> ExprNode <'_, 'code> {
+        ExprNode::Binary {
+            op: self.op,
+            left: &self.left,
+            right: &self.right,
+            span: self.operator
+        }
+    }
+}
+
 impl <T: GetSpan> GetSpan for BinOp <T> {
     fn span(&self) -> Span {
         Span {
+            file: self.left.span().file,
             start: self.left.span().start,
             end: self.right.span().end
         }
@@ -46,11 +71,154 @@ impl <T: ParseDebug> ParseDebug for BinOp <T> {
         f.debug_struct("BinOp")
             .field("left", &self.left.debug(input))
             .field("right", &self.right.debug(input))
-            .field("operator", &self.operator.get_spanned_lines(&input.code)[0])
+            .field("operator", &self.operator.get_spanned_lines(input.source_map.get(self.operator.file).unwrap())[0])
             .finish()
     }
 }
 
+///
+/// An expression with the unary and the binary operators
+///
+/// The binary operators of the same precedence are folded from left to right, `a - b - c` is `(a - b) - c`,
+/// and the unary ones bind tighter than any binary one, so `-a * b` is `(-a) * b`
+///
+#[derive(Clone)]
+pub enum OpExpr <'code> {
+    Unary(Box <UnOp <OpExpr <'code>>>),
+    Binary(Box <BinOp <OpExpr <'code>>>),
+    Operand(Box <TryExpr <'code>>)
+}
+
+impl <'code> GetSpan for OpExpr <'code> {
+    fn span(&self) -> Span {
+        match self {
+            Self::Unary(unary) => unary.span(),
+            Self::Binary(binary) => binary.span(),
+            Self::Operand(operand) => operand.span()
+        }
+    }
+}
+
+impl <'code> From <PrimitiveExpr <'code>> for OpExpr <'code> {
+    fn from(primitive: PrimitiveExpr <'code>) -> Self {
+        Self::Operand(Box::new(primitive.into()))
+    }
+}
+
+impl <'code> AsExprNode <'code> for OpExpr <'code> {
+    fn node(&self) -> ExprNode <'_, 'code> {
+        match self {
+            Self::Unary(unary) => unary.node(),
+            Self::Binary(binary) => binary.node(),
+            Self::Operand(operand) => operand.node()
+        }
+    }
+}
+
+impl <'code> ParseDebug for OpExpr <'code> {
+    fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
+        match self {
+            Self::Unary(unary) => unary.debug_impl(input, f),
+            Self::Binary(binary) => binary.debug_impl(input, f),
+            Self::Operand(operand) => operand.debug_impl(input, f)
+        }
+    }
+}
+
+impl <'code> OpExpr <'code> {
+    pub fn parse(input: &mut ParseInput <'code>, ctx: &impl Context <'code>) -> Result <(Self, TypeIndex)> {
+        Self::parse_with_precedence(input, ctx, None)
+    }
+
+    ///
+    /// Parses the operand followed by the binary operators with precedence higher than `above`, any if it is `None`
+    ///
+    /// The right operand of an operator has only the operators of higher precedence,
+    /// the ones of the same precedence are left for the loop, which makes them left-associative
+    ///
+    fn parse_with_precedence(input: &mut ParseInput <'code>, ctx: &impl Context <'code>, above: Option <Precedence>) -> Result <(Self, TypeIndex)> {
+        let (mut left, mut left_ty) = Self::parse_unary(input, ctx)?;
+
+        loop {
+            let saved_cur_token_for_possible_early_return_triggered_on_inequality_of_spaces_around_the_operator = input.get();
+
+            let (span, operator) = match BinaryOperator::parse(input) {
+                Some((span, operator)) if Some(operator.precedence()) > above => (span, operator),
+                _ => {
+                    input.set(saved_cur_token_for_possible_early_return_triggered_on_inequality_of_spaces_around_the_operator);
+                    break
+                }
+            };
+
+            let (right, right_ty) = Self::parse_with_precedence(input, ctx, Some(operator.precedence()))?;
+
+            // Remove ugly operators format, the tokens on different lines,
+            // which a multiline comment can put there, are never close
+            let are_close = |before: Span, after: Span| before.end.line == after.start.line && after.start.offset.checked_sub(before.end.offset) == Some(1);
+            let are_left_and_operator_close = are_close(left.span(), span);
+            let are_right_and_operator_close = are_close(span, right.span());
+            if are_left_and_operator_close != are_right_and_operator_close {
+                input.set(saved_cur_token_for_possible_early_return_triggered_on_inequality_of_spaces_around_the_operator);
+                break
+            }
+
+            left_ty = match left_ty.perform_binary_operation(input, operator, &right_ty) {
+                Some(x) => x,
+                None => return Result(Err(Error {
+                    span,
+                    message: format!("cannot {operator:?} the `{:?}` and `{:?}` types", left_ty.debug(input), right_ty.debug(input)),
+                    clarifying: format!("incompatible operator and operands")
+                }))
+            };
+
+            left = Self::Binary(Box::new(BinOp {
+                left,
+                right,
+                op: operator,
+                operator: span
+            }))
+        }
+
+        Result(Ok((left, left_ty)))
+    }
+
+    /// Parses the operand with any number of unary operators before it -- `- -a`
+    fn parse_unary(input: &mut ParseInput <'code>, ctx: &impl Context <'code>) -> Result <(Self, TypeIndex)> {
+        let (span, operator) = match UnaryOperator::parse(input) {
+            Some(x) => x,
+            None => {
+                let (operand, ty) = TryExpr::parse(input, ctx)?;
+                return Result(Ok((Self::Operand(Box::new(operand)), ty)))
+            }
+        };
+
+        // A minus right before a literal is folded into it, so that the minimum of the type can be written
+        if operator == UnaryOperator::Neg {
+            if let Ok(literal) = input.int_as_spanned_str().0 {
+                let (int, ty) = IntExpr::from_literal(input, literal, Some(span))?;
+                return Result(Ok((PrimitiveExpr::Int(int).into(), ty)))
+            }
+        }
+
+        let (value, ty) = Self::parse_unary(input, ctx)?;
+
+        let result_ty = match ty.perform_unary_operation(input, operator) {
+            Some(x) => x,
+            None => return Result(Err(Error {
+                span,
+                message: format!("cannot {operator:?} the `{:?}` type", ty.debug(input)),
+                clarifying: format!("incompatible operator and operand")
+            }))
+        };
+
+        Result(Ok((Self::Unary(Box::new(UnOp {
+            value,
+            op: operator,
+            operator: span
+        })), result_ty)))
+    }
+}
+
 #[inline]
 fn unspan(token: Result <&Token>) -> Option <Span> {
     token.0.ok().map(|token| token.span)
@@ -58,31 +226,46 @@ fn unspan(token: Result <&Token>) -> Option <Span> {
 
 macro_rules! precedence {
     (
-        NEXT = $next:ident,
-        'unop: $( $un_name:ident [$($un_fn_debug:ident $un_fn_enum:ident $un_fn:ident )*] )*
-        'binop: $( $bin_name:ident [$($bin_fn_debug:ident $bin_fn_enum:ident $bin_fn:ident )*] )*
+        'unop: [$( $un_fn_debug:ident $un_fn_enum:ident $un_fn:ident )*]
+        'binop: $( $bin_level:ident [$( $bin_fn_debug:ident $bin_fn_enum:ident $bin_fn:ident )*] )*
     ) => {
         #[derive(Copy, Clone, Eq, PartialEq)]
         #[repr(u8)]
         pub enum UnaryOperator {
-            $( $( $un_fn_enum, )* )*
+            $( $un_fn_enum, )*
         }
 
         impl Debug for UnaryOperator {
             fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
-                f.write_str(match self {$($(
+                f.write_str(match self {$(
                     Self::$un_fn_enum => stringify!($un_fn_debug),
-                )*)*})
+                )*})
+            }
+        }
+
+        impl UnaryOperator {
+            /// Parses the operator if it is the next token
+            fn parse(input: &mut ParseInput) -> Option <(Span, Self)> {
+                precedence!(@sign-fun(UnaryOperator) input, $( $un_fn $un_fn_enum )*)
             }
         }
 
+        ///
+        /// The levels of precedence of the binary operators, from the lowest to the highest
+        ///
+        #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+        #[repr(u8)]
+        pub enum Precedence {
+            $( $bin_level, )*
+        }
+
         #[derive(Copy, Clone, Eq, PartialEq)]
         #[repr(u8)]
         pub enum BinaryOperator {
             $( $( $bin_fn_enum, )* )*
         }
 
-         impl Debug for BinaryOperator {
+        impl Debug for BinaryOperator {
             fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
                 f.write_str(match self {$($(
                     Self::$bin_fn_enum => stringify!($bin_fn_debug),
@@ -90,107 +273,20 @@ macro_rules! precedence {
             }
         }
 
-        precedence!(@rev(@un) [$( ($un_name { $($un_fn_debug $un_fn_enum $un_fn )* }) )* ($next)] | []);
-        precedence!(@rev(@bin) [$( ($bin_name { $($bin_fn_debug $bin_fn_enum $bin_fn )* }) )* ($( $un_name )*)] | []);
-
-        precedence!(@expr $( $bin_name )*);
-    };
-
-    (@bin ($( $un_name:ident )*) ($name:ident { $( $fun:tt )* }) ) => {
-        precedence!(@bin-define $name ($( $un_name )*) $( $fun )*);
-    };
-
-    (@bin ($( $un_name:ident )*) ($name:ident { $( $fun:ident )* }) ($next_name:ident { $( $next_fun:tt )* }) $( $tail:tt )*) => {
-        precedence!(@bin-define $name ($next_name) $( $fun )*);
-        precedence!(@bin ($( $un_name )*) ($next_name { $( $next_fun )* }) $( $tail )*);
-    };
-
-    (@bin-define $name:ident ($sub:ident) $( $debug:ident $enumm:ident $fun:ident )*) => {
-        define_expr!($name = BinOp <$sub <'code>>, $sub <'code>);
-
-        impl <'code> $name <'code> {
-            pub fn parse(input: &mut ParseInput <'code>, ctx: &impl Context <'code>) -> Result <(Self, TypeIndex)> {
-                let (left, left_ty) = $sub::parse(input, ctx)?;
-
-                let saved_cur_token_for_possible_early_return_triggered_on_inequality_of_spaces_around_the_operator = input.get();
-
-                Result(Ok(match precedence!(@sign-fun(BinaryOperator) input, $( $fun $enumm )*) {
-                    Some((span, operator)) => {
-                        let (right, right_ty) = $sub::parse(input, ctx)?;
-
-                        // Remove ugly operators format
-                        let are_left_and_operator_close = (span.start.column - left.span().end.column) == 1;
-                        let are_right_and_operator_close = (right.span().start.column - span.end.column) == 1;
-                        if are_left_and_operator_close != are_right_and_operator_close {
-                            input.set(saved_cur_token_for_possible_early_return_triggered_on_inequality_of_spaces_around_the_operator);
-                            return Result(Ok((Self::Partial(Box::new(left)), left_ty)))
-                        }
-
-                        let result_ty = match left_ty.perform_binary_operation(input, operator, &right_ty) {
-                            Some(x) => x,
-                            None => return Result(Err(Error {
-                                span,
-                                message: format!("cannot {operator:?} the `{:?}` and `{:?}` types", left_ty.debug(input), right_ty.debug(input)),
-                                clarifying: format!("incompatible operator and operands"),
-                                filename: input.filename.to_string(),
-                                code: input.code.to_string()
-                            }))
-                        };
-
-                        (Self::Full(Box::new(BinOp {
-                            left,
-                            right,
-                            operator: span
-                        })), result_ty)
-                    },
-                    None => (Self::Partial(Box::new(left)), left_ty)
-                }))
+        impl BinaryOperator {
+            pub const fn precedence(self) -> Precedence {
+                match self {$($(
+                    Self::$bin_fn_enum => Precedence::$bin_level,
+                )*)*}
             }
-        }
-    };
 
-    (@bin-define $name:ident ($head:ident $( $tail:ident )+) $( $fun:tt )*) => {
-        precedence!(@bin-define $name ($( $tail )+) $( $fun )*);
-    };
-
-    (@un ($next:ident) ($name:ident { $( $debug:ident $enumm:ident $fun:ident )* })) => {
-        define_expr!($name = UnOp <$next <'code>>, $next <'code>);
-
-        impl <'code> $name <'code> {
-            pub fn parse(input: &mut ParseInput <'code>, ctx: &impl Context <'code>) -> Result <(Self, TypeIndex)> {
-                Result(Ok(match precedence!(@sign-fun(UnaryOperator) input, $( $fun $enumm )*) {
-                    Some((span, operator)) => {
-                        let (value, ty) = $next::parse(input, ctx)?;
-
-                        let result_ty = match ty.perform_unary_operation(input, operator) {
-                            Some(x) => x,
-                            None => return Result(Err(Error {
-                                span,
-                                message: format!("cannot {operator:?} the `{:?}` type", ty.debug(input)),
-                                clarifying: format!("incompatible operator and operand"),
-                                filename: input.filename.to_string(),
-                                code: input.code.to_string()
-                            }))
-                        };
-
-                        (Self::Full(Box::new(UnOp {
-                            value,
-                            operator: span
-                        })), result_ty)
-                    },
-                    None => {
-                        let (operand, ty) = $next::parse(input, ctx)?;
-                        (Self::Partial(Box::new(operand)), ty)
-                    }
-                }))
+            /// Parses the operator if it is the next token
+            fn parse(input: &mut ParseInput) -> Option <(Span, Self)> {
+                precedence!(@sign-fun(BinaryOperator) input, $( $( $bin_fn $bin_fn_enum )* )*)
             }
         }
     };
 
-    // (@un ($name:ident [$( $fun:ident )*]) $( $tail:tt )+) => {
-    // TODO
-    // };
-
     (@sign-fun($prefix:ident) $input:ident, $single:ident $operator:ident) => {
         unspan($input.$single()).map(|x| (x, $prefix::$operator))
     };
@@ -198,63 +294,61 @@ macro_rules! precedence {
     (@sign-fun($prefix:ident) $input:ident, $head:ident $operator:ident $( $tail:ident )+) => {
         precedence!(@sign-fun($prefix) $input, $head $operator).or_else(|| precedence!(@sign-fun($prefix) $input, $( $tail )+))
     };
+}
 
-    (@rev($( $label:tt )*) [] | [$( $rev:tt )*]) => {
-        precedence! { $( $label )* $( $rev )* }
-    };
-
-    (@rev($( $label:tt )*) [( $( $head:tt )* ) $( ( $( $tail:tt )* ) )*] | [$( $rev:tt )*]) => {
-        precedence! { @rev($( $label )*) [$( ( $( $tail )* ) )*] | [( $( $head )* ) $( $rev )*] }
-    };
-
-    (@expr $last:ident) => {
-        pub(crate) type LastExpr <'code> = $last <'code>;
-
-        #[derive(Clone)]
-        pub struct Expr <'code> {
-            pub value: LastExpr <'code>,
-            pub ty: TypeIndex
-        }
+precedence! {
+    'unop: [pos Pos plus negate Neg minus invert Not exclamation]
 
-        impl <'code> GetSpan for Expr <'code> {
-            fn span(&self) -> Span {
-                self.value.span()
-            }
-        }
+    'binop:
+        Or [disjunct Or or_or]
+        And [conjunct And and_and]
+        Compare [compare Eq eq_eq compare Ne not_eq compare Le less_eq compare Ge greater_eq compare Lt less compare Gt greater]
+        SumDiff [add Add plus sub Sub minus]
+        MulDiv [multiply Mul star divide Div slash]
+}
 
-        impl <'code> ParseDebug for Expr <'code> {
-            fn debug_impl(&self, input: &ParseInput, f: &mut Formatter<'_>) -> FmtResult {
-                f.debug_struct("Expr")
-                    .field("value", &self.value.debug(input))
-                    .field("ty", &self.ty.debug(input))
-                    .finish()
-            }
-        }
+#[derive(Clone)]
+pub struct Expr <'code> {
+    pub value: OpExpr <'code>,
+    pub ty: TypeIndex
+}
 
-        impl <'code> Expr <'code> {
-            pub fn parse(input: &mut ParseInput <'code>, ctx: &impl Context <'code>) -> Result <Self> {
-                let (value, ty) = $last::parse(input, ctx)?;
+impl <'code> GetSpan for Expr <'code> {
+    fn span(&self) -> Span {
+        self.value.span()
+    }
+}
 
-                Result(Ok(Self {
-                    value,
-                    ty
-                }))
-            }
-        }
-    };
+impl <'code> ParseDebug for Expr <'code> {
+    fn debug_impl(&self, input: &ParseInput, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("Expr")
+            .field("value", &self.value.debug(input))
+            .field("ty", &self.ty.debug(input))
+            .finish()
+    }
+}
 
-    (@expr $head:ident $( $tail:ident )+) => {
-        precedence!(@expr $( $tail )+);
-    };
+impl <'code> AsExprNode <'code> for Expr <'code> {
+    fn node(&self) -> ExprNode <'_, 'code> {
+        self.value.node()
+    }
 }
 
-precedence! {
-    NEXT = CallExpr,
+impl <'code> Expr <'code> {
+    pub fn parse(input: &mut ParseInput <'code>, ctx: &impl Context <'code>) -> Result <Self> {
+        let (value, ty) = OpExpr::parse(input, ctx)?;
 
-    'unop:
-        PlusMinusExpr [pos Pos plus negate Neg minus]
+        Result(Ok(Self {
+            value,
+            ty
+        }))
+    }
 
-    'binop:
-        MulDivExpr [multiply Mul star divide Div slash]
-        SumDiffExpr [add Add plus sub Sub minus]
+    /// Wraps the primitive expression of the type `ty` into the expression of the lowest precedence
+    pub fn from_primitive(primitive: PrimitiveExpr <'code>, ty: TypeIndex) -> Self {
+        Self {
+            value: primitive.into(),
+            ty
+        }
+    }
 }
@@ -0,0 +1,127 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use crate::*;
+
+///
+/// Creation of a value of an enum -- `Option::Some 5`, `Some 5` or `None`
+///
+#[derive(Clone)]
+pub struct VariantExpr <'code> {
+    /// The index of the enum in the type bases container
+    pub ty: u32,
+
+    /// The index of the variant in the enum
+    pub variant: usize,
+    pub span: Span,
+    pub args: Vec <Expr <'code>>
+}
+
+impl <'code> GetSpan for VariantExpr <'code> {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl <'code> ParseDebug for VariantExpr <'code> {
+    fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
+        struct Inner <'code, 'a> {
+            input: &'a ParseInput <'code>,
+            args: &'a [Expr <'code>]
+        }
+
+        impl <'code, 'a> Debug for Inner <'code, 'a> {
+            fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+                f.debug_list()
+                    .entries(self.args.iter().map(|x| x.debug(&self.input)))
+                    .finish()
+            }
+        }
+
+        let (base, variant) = self.get_variant(input);
+
+        f.debug_struct("VariantExpr")
+            .field("ty", &base.name)
+            .field("variant", &variant.name)
+            .field("args", &Inner { input, args: &self.args })
+            .finish()
+    }
+}
+
+impl <'code> VariantExpr <'code> {
+    /// Returns the enum and the variant the expression creates
+    ///
+    /// Call only after the types are baked
+    pub fn get_variant <'a> (&self, input: &'a ParseInput <'code>) -> (&'a BakedTypeBase <'code>, &'a Variant <'code>) {
+        let base = match &input.type_bases {
+            TypeBaseContainer::Baked(baked) => &baked[self.ty as usize],
+            _ => unreachable!()
+        };
+
+        match &base.kind {
+            BakedTypeBaseKind::TypeSum(variants) => (base, &variants.vec[self.variant]),
+            _ => unreachable!()
+        }
+    }
+
+    ///
+    /// Finds the variant named `name` among the enums available in `ctx` that are in scope of the current module,
+    /// returning the index of its enum in the type bases container and its index in the enum
+    ///
+    /// If `path` is given, the variant is looked for only in the enum named so with the given index,
+    /// and it is an error if there is no such variant. Otherwise it is an error
+    /// if more than one enum has such a variant
+    ///
+    pub fn resolve(
+        input: &ParseInput <'code>,
+        ctx: &impl Context <'code>,
+        path: Option <(Spanned <&'code str>, Option <u32>)>,
+        name: Spanned <&'code str>
+    ) -> Result <Option <(u32, usize)>> {
+        let baked = match &input.type_bases {
+            TypeBaseContainer::Baked(baked) => baked,
+            _ => unreachable!()
+        };
+
+        let error = |span: Span, message: String, clarifying: String| Result(Err(Error {
+            span,
+            message,
+            clarifying
+        }));
+
+        if let Some((path, ty)) = path {
+            let (ty, variants) = match ty.map(|ty| (ty, &baked[ty as usize].kind)) {
+                Some((ty, BakedTypeBaseKind::TypeSum(variants))) => (ty, variants),
+                _ => return error(path.span, format!("no enum named `{}` found", path.data), String::from("here"))
+            };
+
+            return match variants.vec.iter().position(|variant| variant.name.data == name.data) {
+                Some(variant) => Result(Ok(Some((ty, variant)))),
+                None => error(name.span, format!("no variant named `{}` in the enum `{}`", name.data, path.data), String::from("here"))
+            }
+        }
+
+        let enums = ctx.types().filter_map(|ty| match &baked[ty as usize].kind {
+            BakedTypeBaseKind::TypeSum(variants) if input.is_variant_in_scope(ty, name.data) => Some((ty, baked[ty as usize].name, variants)),
+            _ => None
+        });
+
+        let mut found = enums.filter_map(|(ty, enum_name, variants)| variants.vec
+            .iter()
+            .position(|variant| variant.name.data == name.data)
+            .map(|variant| (ty, enum_name, variant)));
+
+        let (ty, enum_name, variant) = match found.next() {
+            Some(ok) => ok,
+            None => return Result(Ok(None))
+        };
+
+        if let Some((_, other, _)) = found.next() {
+            return error(
+                name.span,
+                format!("the variant `{}` is ambiguous: it is in both `{}` and `{}`", name.data, enum_name.data, other.data),
+                format!("help: write the enum explicitly, like `{}::{}`", enum_name.data, name.data)
+            )
+        }
+
+        Result(Ok(Some((ty, variant))))
+    }
+}
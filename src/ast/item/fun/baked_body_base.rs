@@ -4,11 +4,47 @@ use core::fmt::{Formatter, Result as FmtResult};
 #[derive(Clone)]
 pub enum BakedFnBodyStmt <'code> {
     Expr(Expr <'code>),
+    Binding(Binding <'code>)
+}
+
+impl <'code> BakedFnBodyStmt <'code> {
+    /// Returns the type of the statement, bindings are always `()`
+    pub fn ty(&self) -> TypeIndex {
+        match self {
+            Self::Expr(expr) => expr.ty.clone(),
+            Self::Binding(_) => TypeIndex::UNIT_TUPLE
+        }
+    }
+}
+
+impl <'code> GetSpan for BakedFnBodyStmt <'code> {
+    fn span(&self) -> Span {
+        match self {
+            Self::Expr(expr) => expr.span(),
+            Self::Binding(binding) => binding.span
+        }
+    }
+}
+
+impl <'code> ParseDebug for BakedFnBodyStmt <'code> {
+    fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
+        match self {
+            Self::Expr(expr) => expr.debug_impl(input, f),
+            Self::Binding(binding) => binding.debug_impl(input, f)
+        }
+    }
 }
 
 #[derive(Clone)]
 pub struct BakedFnBodyBase <'code> {
-    pub body: Vec <Expr <'code>>
+    pub body: Vec <BakedFnBodyStmt <'code>>
+}
+
+impl <'code> BakedFnBodyBase <'code> {
+    /// The body standing in for the one with errors
+    pub const EMPTY: Self = Self {
+        body: vec![]
+    };
 }
 
 impl <'code> ParseDebug for BakedFnBodyBase <'code> {
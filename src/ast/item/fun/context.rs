@@ -1,11 +1,15 @@
 use crate::*;
+use std::rc::Rc;
 
 impl <'code> Fn <'code> {
     pub fn get_context <'fun, 'items> (&'fun self, items: &'items [Item <'code>]) -> FnContext <'fun, 'items, 'code> {
         FnContext {
             fun: self,
             items,
-            is_primary_call: true
+            locals: None,
+            is_primary_call: true,
+            is_argument: false,
+            is_after_tilde: false
         }
     }
 }
@@ -13,25 +17,91 @@ impl <'code> Fn <'code> {
 pub struct FnContext <'fun, 'items, 'code> {
     fun: &'fun Fn <'code>,
     items: &'items [Item <'code>],
-    is_primary_call: bool
+
+    /// Variables bound in the body, shared with the contexts derived from this one
+    locals: Option <Rc <Scope <'code>>>,
+    is_primary_call: bool,
+    is_argument: bool,
+    is_after_tilde: bool
+}
+
+///
+/// The variables of one binding or one `match` arm together with the ones bound before them
+///
+/// The scopes are never changed once made, so a nested context shares them instead of copying
+///
+struct Scope <'code> {
+    variables: Vec <TypedVariable <'code>>,
+    outer: Option <Rc <Scope <'code>>>
+}
+
+///
+/// The iterator over the variables bound in the body, from the latest to the earliest
+///
+pub struct Locals <'a, 'code> {
+    variables: core::iter::Rev <core::slice::Iter <'a, TypedVariable <'code>>>,
+    outer: Option <&'a Scope <'code>>
+}
+
+impl <'a, 'code> Iterator for Locals <'a, 'code> {
+    type Item = &'a TypedVariable <'code>;
+
+    fn next(&mut self) -> Option <Self::Item> {
+        loop {
+            if let Some(variable) = self.variables.next() {
+                return Some(variable)
+            }
+
+            let scope = self.outer?;
+            self.variables = scope.variables.iter().rev();
+            self.outer = scope.outer.as_deref()
+        }
+    }
 }
 
 impl <'fun, 'items, 'code> Context <'code> for FnContext <'fun, 'items, 'code> {
-    type VariablesIter <'a> = core::slice::Iter <'a, TypedVariable <'code>> where 'code: 'a, Self: 'a;
+    type VariablesIter <'a> = core::iter::Chain <Locals <'a, 'code>, core::slice::Iter <'a, TypedVariable <'code>>> where 'code: 'a, Self: 'a;
 
     type FunctionsIter <'a> = core::iter::FilterMap <core::iter::Enumerate <core::slice::Iter <'a, Item <'code>>>, for <'b> fn((usize, &'b Item <'code>)) -> Option <(usize, &'b Fn <'code>)>> where 'code: 'a, Self: 'a;
 
+    type TypesIter <'a> = core::iter::FilterMap <core::slice::Iter <'a, Item <'code>>, for <'b> fn(&'b Item <'code>) -> Option <u32>> where 'code: 'a, Self: 'a;
+
     fn variables <'a> (&'a self) -> Self::VariablesIter <'a> {
-        self.fun.args.iter()
+        let locals = Locals {
+            variables: [].iter().rev(),
+            outer: self.locals.as_deref()
+        };
+
+        locals.chain(self.fun.args.iter())
     }
 
     fn functions <'a> (&'a self) -> Self::FunctionsIter <'a> {
         self.items.iter().enumerate().filter_map(|(idx, item)| match item {
-            Item::Fn(fun) => Some((idx, fun)),
+            // The instances are called through the generic functions
+            Item::Fn(fun) if !fun.is_instance() => Some((idx, fun)),
+            _ => None
+        })
+    }
+
+    fn types <'a> (&'a self) -> Self::TypesIter <'a> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Ty(ty) | Item::Enum(ty) => Some(ty.type_base_index()),
             _ => None
         })
     }
 
+    fn type_arguments(&self) -> (&[u32], &[TypeIndex]) {
+        (&self.fun.type_params, &self.fun.type_args)
+    }
+
+    fn owner(&self) -> Option <u32> {
+        self.fun.owner
+    }
+
+    fn return_type(&self) -> &TypeIndex {
+        &self.fun.ret_ty
+    }
+
     fn is_primary_call(&self) -> bool {
         self.is_primary_call
     }
@@ -40,7 +110,58 @@ impl <'fun, 'items, 'code> Context <'code> for FnContext <'fun, 'items, 'code> {
         Self {
             fun: self.fun,
             items: self.items,
-            is_primary_call: false
+            locals: self.locals.clone(),
+            is_primary_call: false,
+            is_argument: true,
+            is_after_tilde: self.is_after_tilde
         }
     }
+
+    fn is_argument(&self) -> bool {
+        self.is_argument
+    }
+
+    fn is_after_tilde(&self) -> bool {
+        self.is_after_tilde
+    }
+
+    fn enter_tilde(&self) -> Self {
+        Self {
+            fun: self.fun,
+            items: self.items,
+            locals: self.locals.clone(),
+            is_primary_call: true,
+            is_argument: false,
+            is_after_tilde: true
+        }
+    }
+
+    fn enter_braces(&self) -> Self {
+        Self {
+            fun: self.fun,
+            items: self.items,
+            locals: self.locals.clone(),
+            is_primary_call: false,
+            is_argument: false,
+            is_after_tilde: false
+        }
+    }
+
+    fn enter_block(&self) -> Self {
+        Self {
+            fun: self.fun,
+            items: self.items,
+            locals: self.locals.clone(),
+            is_primary_call: true,
+            is_argument: false,
+            is_after_tilde: false
+        }
+    }
+
+    fn bind_variables(&mut self, variables: Vec <TypedVariable <'code>>) {
+        self.locals = Some(Rc::new(Scope {
+            variables,
+            outer: self.locals.take()
+        }))
+    }
 }
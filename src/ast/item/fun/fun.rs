@@ -1,9 +1,41 @@
 use crate::*;
 use core::fmt::{Debug, Formatter, Write, Result as FmtResult};
 
+/// Who can call a function
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Visibility {
+    /// Everyone
+    Public,
+
+    /// Only the functions of the same type
+    Private
+}
+
 #[derive(Clone)]
 pub struct Fn <'code> {
     pub name: Spanned <&'code str>,
+
+    /// The doc comments written before the function
+    pub doc: Doc <'code>,
+
+    /// The type the function belongs to, it is called as `Type::function` then
+    pub owner: Option <u32>,
+
+    /// The index of the module the function is written in
+    pub module: u32,
+    pub visibility: Visibility,
+
+    /// Whether the function is not written in the code but generated, like `new` of the `ty` types
+    pub is_generated: bool,
+
+    /// Whether the function is a method, which takes the value of its `owner` as the first argument named `i`
+    pub is_method: bool,
+
+    /// The type parameters, the indices in `ParseInput.type_params`
+    pub type_params: Vec <u32>,
+
+    /// The types the type parameters stand for if the function is an instance of a generic one, empty otherwise
+    pub type_args: Vec <TypeIndex>,
     pub args: Vec <TypedVariable <'code>>,
     pub ret_ty: TypeIndex,
     pub body: FnBodyIndex
@@ -11,7 +43,20 @@ pub struct Fn <'code> {
 
 impl <'code> ParseDebug for Fn <'code> {
     fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
+        self.doc.fmt(f)?;
+        if self.visibility == Visibility::Private {
+            f.write_str("private ")?;
+        }
+        if let Some(owner) = self.owner {
+            TypeIndex::Scalar(owner).debug_impl(input, f)?;
+            f.write_str("::")?;
+        }
 		self.name.fmt(f)?;
+        if !self.type_args.is_empty() {
+            TypeIndex::debug_args(&self.type_args, input, f)?;
+        } else if !self.type_params.is_empty() {
+            TypeIndex::debug_args(&self.type_params.iter().map(|param| TypeIndex::Generic(*param)).collect::<Vec <_>>(), input, f)?;
+        }
         f.write_char(' ')?;
 		print_punctuated_seq::<_, ", ">(self.args.iter().map(|i| i.debug(input)), f)?;
 
@@ -27,15 +72,46 @@ impl <'code> ParseDebug for Fn <'code> {
     }
 }
 
+impl <'code> Fn <'code> {
+    /// Returns whether the function has type parameters and is not an instance for concrete types
+    pub fn is_generic(&self) -> bool {
+        !self.type_params.is_empty() && self.type_args.is_empty()
+    }
+
+    /// Returns whether the function is an instance of a generic one for concrete types
+    pub fn is_instance(&self) -> bool {
+        !self.type_args.is_empty()
+    }
+}
+
 impl <'code> Parse <'code> for Fn <'code> {
     ///
-    /// Parses raw fn, i.e. with body left unparsed
+    /// Parses raw fn, i.e. with body left unparsed, together with the doc comments before it
     ///
     fn parse_impl(input: &mut ParseInput <'code>) -> Result <Self> {
+        let doc = Doc::parse(input);
+
+        let fun = Self::parse_with_type_params(input, &[])?;
+
+        Result(Ok(Self {
+            doc,
+            ..fun
+        }))
+    }
+}
+
+impl <'code> Fn <'code> {
+    ///
+    /// Parses raw fn, which can use the type parameters `outer` of the type it is written in
+    /// in addition to its own ones
+    ///
+    /// The doc comments before the function are left for the caller
+    ///
+    pub fn parse_with_type_params(input: &mut ParseInput <'code>, outer: &[u32]) -> Result <Self> {
         fn stop(input: &mut ParseInput) -> Option <bool> {
             if input.arrow().0.is_ok() {
                 Some(true)
-            } else if input.is_exhausted() || unsafe { check_if_the_next_token_is_newline(input) } {
+            } else if input.is_exhausted() || check_if_the_next_token_is_newline(input) {
                 Some(false)
             } else {
                 None
@@ -44,6 +120,11 @@ impl <'code> Parse <'code> for Fn <'code> {
 
         let name = input.ident_as_spanned_str()?;
 
+        let own = input.type_params_declaration()?;
+
+        let type_params = outer.iter().copied().chain(own).collect::<Vec <_>>();
+        input.type_params_in_scope = type_params.clone();
+
         let mut args = vec![];
 
         let stopped_by_arrow = loop {
@@ -72,6 +153,14 @@ impl <'code> Parse <'code> for Fn <'code> {
 
         Result(Ok(Self {
             name,
+            doc: Doc::EMPTY,
+            owner: None,
+            module: input.module,
+            visibility: Visibility::Public,
+            is_generated: false,
+            is_method: false,
+            type_params,
+            type_args: vec![],
             args,
             ret_ty,
             body
@@ -0,0 +1,60 @@
+use crate::*;
+
+/// A generic function can have at most this many instances, more of them most likely means
+/// that it calls itself with the types growing with every call, so the instantiation would never end
+const MAX_INSTANCES_OF_ONE_FUNCTION: usize = 64;
+
+/// The type arguments of an instance can consist of at most this many types,
+/// since the types growing with every call may grow exponentially
+const MAX_TYPE_ARGS_LENGTH: usize = 1024;
+
+///
+/// The instances of generic functions for concrete type arguments
+///
+/// They are requested while the bodies are baked, baked after all the functions written in the code
+/// and appended to `ParseInput.top_level_items` in the order they were requested in
+///
+pub struct FnInstances {
+    /// The index the first instance is going to have in `ParseInput.top_level_items`
+    pub first_item: usize,
+
+    /// The indices of the generic functions in `ParseInput.top_level_items` together with the type arguments
+    pub list: Vec <(usize, Vec <TypeIndex>)>
+}
+
+impl Default for FnInstances {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FnInstances {
+    pub const fn new() -> Self {
+        Self {
+            first_item: 0,
+            list: vec![]
+        }
+    }
+
+    ///
+    /// Returns the index in `ParseInput.top_level_items` the instance of the generic function `fun`
+    /// for the concrete type arguments `args` is going to have, requesting it if there is none yet
+    ///
+    /// Returns `None` if the function has too many instances already or the type arguments are too long
+    ///
+    pub fn instantiate(&mut self, fun: usize, args: Vec <TypeIndex>) -> Option <usize> {
+        if let Some(idx) = self.list.iter().position(|(of, of_args)| *of == fun && *of_args == args) {
+            return Some(self.first_item + idx)
+        }
+
+        let too_long = args.iter().map(TypeIndex::length).sum::<usize>() > MAX_TYPE_ARGS_LENGTH;
+
+        if too_long || self.list.iter().filter(|(of, _)| *of == fun).count() == MAX_INSTANCES_OF_ONE_FUNCTION {
+            return None
+        }
+
+        self.list.push((fun, args));
+
+        Some(self.first_item + self.list.len() - 1)
+    }
+}
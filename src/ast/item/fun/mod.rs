@@ -1 +1 @@
-crate::modules!(raw_body_base baked_body_base body_index container context fun);
+crate::modules!(raw_body_base baked_body_base body_index container context fun instances);
@@ -1,39 +1,42 @@
 use crate::*;
-use core::ptr::NonNull;
 use core::fmt::{Debug, Formatter, Result as FmtResult};
 
 #[derive(Clone)]
-pub struct RawFnBodyBase <'code> {
-    body: NonNull <[Token <'code>]>
+pub enum RawFnBodyBase <'code> {
+    /// The tokens of the body written in the code
+    Code(&'code [Token <'code>]),
+
+    /// The body of the `new` generated for the `ty` type with this index,
+    /// it creates the value of the type from the arguments
+    New(u32)
 }
 
 impl <'code> Parse <'code> for RawFnBodyBase <'code> {
     fn parse_impl(input: &mut ParseInput <'code>) -> Result <Self> {
         input.newline()?;
 
-		let body = input.find_end_of_block_and_return_everything_in_it_and_also_go_forward_to_its_end(1);
+        let body = input.find_end_of_block_and_return_everything_in_it_and_also_go_forward_to_its_end();
 
-        Result(Ok(Self {
-            body
-        }))
+        Result(Ok(Self::Code(body)))
     }
 }
 
 impl <'code> RawFnBodyBase <'code> {
-    /// # Safety
-    ///
-    /// At the moment of call of this function the `input`
-    /// that was passed to it should still be valid
-    pub fn body(&self) -> &'code [Token <'code>] {
-        // SAFETY: caller must uphold the contract
-        unsafe { self.body.as_ref() }
+    /// Returns the tokens of the body, `None` if the body is generated
+    pub fn body(&self) -> Option <&'code [Token <'code>]> {
+        match self {
+            Self::Code(body) => Some(body),
+            Self::New(_) => None
+        }
     }
 }
 
 impl <'code> Debug for RawFnBodyBase <'code> {
     #[inline(always)]
     fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
-        self.body().fmt(f)
+        match self {
+            Self::Code(body) => body.fmt(f),
+            Self::New(ty) => f.debug_tuple("New").field(ty).finish()
+        }
     }
 }
-
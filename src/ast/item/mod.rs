@@ -7,7 +7,8 @@ use crate::*;
 #[repr(u8)]
 pub enum Item <'code> {
 	Fn(Fn <'code>),
-	Ty(RawTypeDefinitionIndex)
+	Ty(RawTypeDefinitionIndex),
+	Enum(RawTypeDefinitionIndex)
 }
 
 impl <'code> ParseDebug for Item <'code> {
@@ -15,7 +16,7 @@ impl <'code> ParseDebug for Item <'code> {
 		f.write_str("Item(")?;
 		match self {
 			Self::Fn(fun) => fun.debug_impl(input, f),
-			Self::Ty(ty) => ty.debug_impl(input, f)
+			Self::Ty(ty) | Self::Enum(ty) => ty.debug_impl(input, f)
 		}?;
 		f.write_char(')')
 	}
@@ -33,7 +34,11 @@ macro_rules! tryok {
 
 impl <'code> Parse <'code> for Item <'code> {
 	fn parse_impl(input: &mut ParseInput <'code>) -> Result <Self> {
-		let ty = tryok!(Ty, RawTypeDefinitionIndex::parse_with_returning_cur(input));
+		let ty = if check_if_the_next_token_is_keyword(input, &["enum"]) {
+			tryok!(Enum, RawTypeDefinitionIndex::parse_with_returning_cur(input))
+		} else {
+			tryok!(Ty, RawTypeDefinitionIndex::parse_with_returning_cur(input))
+		};
 		let fun = tryok!(Fn, Fn::parse_with_returning_cur(input));
 
 		if ty.0 > fun.0 {
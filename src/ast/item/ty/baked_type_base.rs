@@ -5,7 +5,8 @@ use core::fmt::{Formatter, Write, Result as FmtResult};
 #[repr(u8)]
 pub enum BakedTypeBaseKind <'code> {
     Builtin(usize),
-    TypeProduct(Punctuated <'code, TypedVariable <'code>, "\n\t">)
+    TypeProduct(Punctuated <'code, TypedVariable <'code>, "\n\t">),
+    TypeSum(Punctuated <'code, Variant <'code>, "\n\t">)
 }
 
 impl <'code> ParseDebug for BakedTypeBaseKind <'code> {
@@ -18,6 +19,11 @@ impl <'code> ParseDebug for BakedTypeBaseKind <'code> {
                 f.write_str("TypeProduct(")?;
                 fields.debug_impl(input, f)?;
                 f.write_char(')')
+            },
+            Self::TypeSum(variants) => {
+                f.write_str("TypeSum(")?;
+                variants.debug_impl(input, f)?;
+                f.write_char(')')
             }
         }?;
 
@@ -25,10 +31,41 @@ impl <'code> ParseDebug for BakedTypeBaseKind <'code> {
     }
 }
 
+impl <'code> BakedTypeBaseKind <'code> {
+    /// Replaces the type parameters `params` in the types of the fields or the payloads with the corresponding `args`
+    pub fn substitute(&self, input: &mut ParseInput, params: &[u32], args: &[TypeIndex]) -> Self {
+        match self {
+            Self::Builtin(idx) => Self::Builtin(*idx),
+            Self::TypeProduct(fields) => Self::TypeProduct(Punctuated::wrap(fields.vec.iter().map(|field| TypedVariable {
+                name: field.name,
+                doc: field.doc.clone(),
+                ty: field.ty.substitute(input, params, args)
+            }).collect())),
+            Self::TypeSum(variants) => Self::TypeSum(Punctuated::wrap(variants.vec.iter().map(|variant| Variant {
+                name: variant.name,
+                doc: variant.doc.clone(),
+                payload: variant.payload.iter().map(|ty| ty.substitute(input, params, args)).collect()
+            }).collect()))
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct BakedTypeBase <'code> {
     pub kind: BakedTypeBaseKind <'code>,
-    pub name: Spanned <&'code str>
+    pub name: Spanned <&'code str>,
+
+    /// The doc comments written before the type, the instances of generic types share them
+    pub doc: Doc <'code>,
+
+    /// The module defining the type, `None` for the builtin ones
+    pub module: Option <u32>,
+
+    /// The type parameters of a generic type, the indices in `ParseInput.type_params`
+    pub params: Vec <u32>,
+
+    /// The generic type and the type arguments this type is the instance for
+    pub instance_of: Option <(u32, Vec <TypeIndex>)>
 }
 
 impl <'code> BakedTypeBase <'code> {
@@ -38,16 +75,35 @@ impl <'code> BakedTypeBase <'code> {
             name: Spanned {
                 data: name,
                 span: Span::DEFAULT
-            }
+            },
+            doc: Doc::EMPTY,
+            module: None,
+            params: vec![],
+            instance_of: None
         }
     }
 }
 
 impl <'code> ParseDebug for BakedTypeBase <'code> {
     fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
-        f.debug_struct("BakedTypeBase")
+        let mut builder = f.debug_struct("BakedTypeBase");
+
+        builder
             .field("kind", &self.kind.debug(input))
-            .field("name", &self.name)
-        .finish()
+            .field("name", &self.name);
+
+        if !self.doc.is_empty() {
+            builder.field("doc", &self.doc.lines);
+        }
+
+        if !self.params.is_empty() {
+            builder.field("params", &self.params.iter().map(|param| input.type_params[*param as usize].data).collect::<Vec <_>>());
+        }
+
+        if let Some((_, args)) = &self.instance_of {
+            builder.field("args", &args.iter().map(|arg| arg.debug(input)).collect::<Vec <_>>());
+        }
+
+        builder.finish()
     }
 }
@@ -2,38 +2,88 @@ use crate::*;
 
 macro_rules! builtin_types {
     ($vis:vis const $name:ident = [$( ( $($expr:tt)* ) )*]) => {
-        $vis const $name: [BuiltinType; builtin_types!(@count $( ($($expr)*) )*)] = [builtin_types!(@iter [0] $( ( $($expr)* ) )*)];
+        $vis const $name: [BuiltinType; builtin_types!(@count $( ($($expr)*) )*)] = builtin_types!(@iter [0] [] $( ( $($expr)* ) )*);
     };
 
-    (@iter [$idx:expr] ($name:literal category: numerik)) => {
+    (@single [$idx:expr] ($name:literal category: signed numerik)) => {
         BuiltinType::new($idx, $name, &[
             BuiltinUnaryOperation::new(UnaryOperator::Pos),
             BuiltinUnaryOperation::new(UnaryOperator::Neg)
         ],
+        builtin_types!(@arithmetik))
+    };
+
+    (@single [$idx:expr] ($name:literal category: unsigned numerik)) => {
+        BuiltinType::new($idx, $name, &[
+            BuiltinUnaryOperation::new(UnaryOperator::Pos)
+        ],
+        builtin_types!(@arithmetik))
+    };
+
+    (@single [$idx:expr] ($name:literal category: float numerik)) => {
+        builtin_types!(@single [$idx] ($name category: signed numerik))
+    };
+
+    (@single [$idx:expr] ($name:literal category: logik)) => {
+        BuiltinType::new($idx, $name, &[
+            BuiltinUnaryOperation::new(UnaryOperator::Not)
+        ],
+        &[
+            BuiltinBinaryOperation::new(BinaryOperator::Eq),
+            BuiltinBinaryOperation::new(BinaryOperator::Ne),
+            BuiltinBinaryOperation::new(BinaryOperator::And),
+            BuiltinBinaryOperation::new(BinaryOperator::Or),
+        ])
+    };
+
+    (@iter [$idx:expr] [$( $done:expr, )*]) => {
+        [$( $done ),*]
+    };
+
+    (@iter [$idx:expr] [$( $done:expr, )*] ($( $head:tt )*) $( $tail:tt )*) => {
+        builtin_types!(@iter [$idx + 1] [$( $done, )* builtin_types!(@single [$idx] ($( $head )*)),] $( $tail )*)
+    };
+
+    (@arithmetik) => {
         &[
             BuiltinBinaryOperation::new(BinaryOperator::Mul),
             BuiltinBinaryOperation::new(BinaryOperator::Div),
             BuiltinBinaryOperation::new(BinaryOperator::Add),
             BuiltinBinaryOperation::new(BinaryOperator::Sub),
-        ])
-    };
-
-    (@iter [$idx:expr] ($( $head:tt )*) $( $tail:tt )+) => {
-        builtin_types!(@iter [$idx] ($( $head )*)),
-        builtin_types!(@iter [$idx + 1] $( $tail )+)
+            BuiltinBinaryOperation::with_result(BinaryOperator::Eq, "bool"),
+            BuiltinBinaryOperation::with_result(BinaryOperator::Ne, "bool"),
+            BuiltinBinaryOperation::with_result(BinaryOperator::Le, "bool"),
+            BuiltinBinaryOperation::with_result(BinaryOperator::Ge, "bool"),
+            BuiltinBinaryOperation::with_result(BinaryOperator::Lt, "bool"),
+            BuiltinBinaryOperation::with_result(BinaryOperator::Gt, "bool"),
+        ]
     };
 
-    (@count ($( $tt:tt )*)) => {
-        1
+    (@count) => {
+        0
     };
 
-    (@count ($( $head:tt )*) $( $tail:tt )+) => {
-        builtin_types!(@ $head) + builtin_types!(@ $( $tail )*)
+    (@count ($( $head:tt )*) $( $tail:tt )*) => {
+        1 + builtin_types!(@count $( $tail )*)
     };
 }
 
 builtin_types!(pub const BUILTIN_BAKED_TYPES = [
-    ("i32" category: numerik)
+    ("i8" category: signed numerik)
+    ("i16" category: signed numerik)
+    ("i32" category: signed numerik)
+    ("i64" category: signed numerik)
+    ("i128" category: signed numerik)
+    ("isize" category: signed numerik)
+    ("u8" category: unsigned numerik)
+    ("u16" category: unsigned numerik)
+    ("u32" category: unsigned numerik)
+    ("u64" category: unsigned numerik)
+    ("u128" category: unsigned numerik)
+    ("usize" category: unsigned numerik)
+    ("f32" category: float numerik)
+    ("f64" category: float numerik)
+    ("bool" category: logik)
 ]);
 
 pub struct BuiltinType {
@@ -70,13 +120,25 @@ impl BuiltinUnaryOperation {
 }
 
 pub struct BuiltinBinaryOperation {
-    pub op: BinaryOperator
+    pub op: BinaryOperator,
+
+    /// The name of the builtin type the operation results in,
+    /// `None` means it is the type of the operands
+    pub result: Option <&'static str>
 }
 
 impl BuiltinBinaryOperation {
     pub const fn new(op: BinaryOperator) -> Self {
         Self {
-            op
+            op,
+            result: None
+        }
+    }
+
+    pub const fn with_result(op: BinaryOperator, result: &'static str) -> Self {
+        Self {
+            op,
+            result: Some(result)
         }
     }
 }
@@ -1,18 +1,61 @@
 use crate::*;
 use core::fmt::{Debug, Formatter, Write, Result as FmtResult};
 
+///
+/// What a type is made of
+///
+#[derive(Clone)]
+pub enum RawTypeDefinitionKind <'code> {
+    /// `ty` -- all the fields at once
+    Product(Punctuated <'code, TypedVariable <'code>, "\n\t">),
+
+    /// `enum` -- exactly one of the variants
+    Sum(Punctuated <'code, Variant <'code>, "\n\t">)
+}
+
 #[derive(Clone)]
 pub struct RawTypeDefinition <'code> {
     pub name: Spanned <&'code str>,
-    pub fields: Punctuated <'code, TypedVariable <'code>, "\n\t">
+
+    /// The doc comments written before the type and its attributes
+    pub doc: Doc <'code>,
+
+    /// The index of the module the type is defined in
+    pub module: u32,
+
+    /// The type parameters, the indices in `ParseInput.type_params`
+    pub params: Vec <u32>,
+    pub kind: RawTypeDefinitionKind <'code>,
+
+    /// The visibility of the generated `new` of a `ty` type, `None` if it is not generated
+    pub new: Option <Visibility>,
+
+    /// The methods and the `statik` functions written in the type,
+    /// they become items once the index of the type is known
+    pub functions: Vec <Fn <'code>>
 }
 
 impl <'code> ParseDebug for RawTypeDefinition <'code> {
     fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
-        f.write_str("ty ")?;
+        self.doc.fmt(f)?;
+        match (&self.kind, self.new) {
+            (RawTypeDefinitionKind::Product(_), None) => f.write_str("@default new = none\n")?,
+            (RawTypeDefinitionKind::Product(_), Some(Visibility::Private)) => f.write_str("@default new = private\n")?,
+            _ => ()
+        }
+        f.write_str(match self.kind {
+            RawTypeDefinitionKind::Product(_) => "ty ",
+            RawTypeDefinitionKind::Sum(_) => "enum "
+        })?;
         self.name.fmt(f)?;
+        if !self.params.is_empty() {
+            TypeIndex::debug_args(&self.params.iter().map(|param| TypeIndex::Generic(*param)).collect::<Vec <_>>(), input, f)?;
+        }
         f.write_char('\n')?;
-        self.fields.debug_impl(input, f)?;
+        match &self.kind {
+            RawTypeDefinitionKind::Product(fields) => fields.debug_impl(input, f),
+            RawTypeDefinitionKind::Sum(variants) => variants.debug_impl(input, f)
+        }?;
         f.write_char('\n')?;
 
         Ok(())
@@ -21,55 +64,235 @@ impl <'code> ParseDebug for RawTypeDefinition <'code> {
 
 impl <'code> Parse <'code> for RawTypeDefinition <'code> {
     fn parse_impl(input: &mut ParseInput <'code>) -> Result <Self> {
-		input.keyword("ty")?;
+        let doc = Doc::parse(input);
+
+        let (new, attribute) = Self::parse_attributes(input)?;
+
+        let cur = input.get();
+        let is_enum = input.keyword("enum").0.is_ok();
+        if !is_enum {
+            input.set(cur);
+            input.keyword("ty")?;
+        }
+
+        if let (true, Some(attribute)) = (is_enum, attribute) {
+            return Result(Err(Error {
+                span: attribute,
+                message: String::from("the `@default` attribute is only for `ty` types"),
+                clarifying: String::from("enums have nothing generated")
+            }))
+        }
+
         let name = input.ident_as_spanned_str()?;
 
+        let params = input.type_params_declaration()?;
+
         input.newline()?;
 
-        let block = input.find_end_of_block_and_return_everything_in_it_and_also_go_forward_to_its_end(1);
-        let block = unsafe { block.as_ref() };
+        let block = input.find_end_of_block_and_return_everything_in_it_and_also_go_forward_to_its_end();
+
+        let old_token_stream = core::mem::replace(&mut input.stream, TokenStream::from(block));
+
+        let mut functions = vec![];
+
+        let kind = if is_enum {
+            Self::parse_lines(input, &params, &mut functions, |input, doc| Result(Variant::parse(input).0.map(|variant| vec![Variant {
+                doc,
+                ..variant
+            }])))
+                .0
+                .and_then(|variants| Self::check_variants(&variants).0.map(|_| RawTypeDefinitionKind::Sum(Punctuated::wrap(variants))))
+        } else {
+            Self::parse_lines(input, &params, &mut functions, |input, doc| Result(TypedVariable::parse(input).0.map(|fields| fields.into_iter().map(|field| TypedVariable {
+                doc: doc.clone(),
+                ..field
+            }).collect())))
+                .0
+                .map(|fields| RawTypeDefinitionKind::Product(Punctuated::wrap(fields)))
+        };
+
+        input.stream = old_token_stream;
+
+        let kind = Result(kind)?;
+
+        Self::check_functions(&kind, &functions)?;
+
+        Result(Ok(Self {
+            name,
+            doc,
+            module: input.module,
+            params,
+            kind,
+            new,
+            functions
+        }))
+    }
+}
 
-        let mut fields = Vec::with_capacity(block.len());
+impl <'code> RawTypeDefinition <'code> {
+    ///
+    /// Parses the attributes written before the definition, each on its own line
+    ///
+    /// Returns the visibility of the generated `new` set by `@default new = none/private/public`
+    /// and the span of the first attribute, if there are any
+    ///
+    fn parse_attributes(input: &mut ParseInput <'code>) -> Result <(Option <Visibility>, Option <Span>)> {
+        let mut new = Some(Visibility::Public);
+        let mut first = None;
 
-        let old_token_stream = core::mem::replace(&mut input.stream, TokenStream::empty());
+        while let Ok(at) = input.at().0.map(|at| at.span) {
+            let name = input.ident_as_spanned_str()?;
 
-        input.stream = TokenStream::from(block);
+            first.get_or_insert(Span {
+                file: at.file,
+                start: at.start,
+                end: name.span.end
+            });
+
+            let error = |span: Span, message: String, clarifying: &str| Result(Err(Error {
+                span,
+                message,
+                clarifying: String::from(clarifying)
+            }));
+
+            if name.data != "default" {
+                return error(name.span, format!("unknown attribute `@{}`", name.data), "help: the types can have only the `@default` attribute")
+            }
+
+            let part = input.ident_as_spanned_str()?;
+
+            if part.data != "new" {
+                return error(part.span, format!("unknown part `{}` of the `@default` attribute", part.data), "help: only `new` can be set, like `@default new = private`")
+            }
+
+            input.eq()?;
+
+            let value = input.ident_as_spanned_str()?;
+
+            new = match value.data {
+                "none" => None,
+                "private" => Some(Visibility::Private),
+                "public" => Some(Visibility::Public),
+                _ => return error(value.span, format!("unknown value `{}` of `@default new`", value.data), "help: it can be `none`, `private` or `public`")
+            };
+
+            input.newline()?;
+        }
+
+        Result(Ok((new, first)))
+    }
+
+    ///
+    /// Parses every line left in the stream with `parser`, except the functions of the type,
+    /// which are put into `functions`
+    ///
+    /// The doc comments before each line are given to `parser` or put into the function
+    ///
+    /// `params` are the type parameters of the type
+    ///
+    fn parse_lines <T> (
+        input: &mut ParseInput <'code>,
+        params: &[u32],
+        functions: &mut Vec <Fn <'code>>,
+        parser: impl core::ops::Fn(&mut ParseInput <'code>, Doc <'code>) -> Result <Vec <T>>
+    ) -> Result <Vec <T>> {
+        let mut parsed = vec![];
 
         while !input.is_exhausted() {
-            remove_newlines_and_tabs(input);
+            let doc = Doc::parse(input);
 
             if input.is_exhausted() {
                 break
             }
 
-            let set = match TypedVariable::parse(input).0 {
-                Ok(ok) => ok,
-                Err(err) =>  {
-                    input.stream = old_token_stream;
-                    return Result(Err(err))
+            let is_statik = check_if_the_next_token_is_keyword(input, &["statik"]);
+
+            if is_statik || Self::is_followed_by_block(input) {
+                if is_statik {
+                    input.go_forward()
                 }
-            };
 
-            fields.extend(set)
+                let mut fun = Fn::parse_with_type_params(input, params)?;
+                fun.doc = doc;
+                fun.is_method = !is_statik;
+                functions.push(fun);
+
+                // The function has replaced the type parameters in scope with its own ones
+                input.type_params_in_scope = params.to_vec()
+            } else {
+                parsed.extend(parser(input, doc)?)
+            }
         }
 
-        input.stream = old_token_stream;
+        Result(Ok(parsed))
+    }
 
-        let fields = Punctuated::wrap(fields);
+    /// Returns whether the current line is followed by an indented block, i.e. it starts a function
+    fn is_followed_by_block(input: &ParseInput <'code>) -> bool {
+        let rest = &input.stream.buf[input.get()..];
 
-        Result(Ok(Self {
-            name,
-            fields
-        }))
+        let newline = match rest.iter().position(|token| token.kind == TokenKind::Newline) {
+            Some(newline) => newline,
+            None => return false
+        };
+
+        // Empty lines are skipped
+        rest[newline..].iter().find(|token| token.kind != TokenKind::Newline).is_some_and(|token| token.kind == TokenKind::Indent)
+    }
+
+    /// Checks that the functions of the type have different names and do not share them with the fields or the variants
+    fn check_functions(kind: &RawTypeDefinitionKind <'code>, functions: &[Fn <'code>]) -> Result <()> {
+        let members = match kind {
+            RawTypeDefinitionKind::Product(fields) => fields.vec.iter().map(|field| field.name).collect::<Vec <_>>(),
+            RawTypeDefinitionKind::Sum(variants) => variants.vec.iter().map(|variant| variant.name).collect()
+        };
+
+        for (idx, fun) in functions.iter().enumerate() {
+            let previous = members
+                .iter()
+                .chain(functions[..idx].iter().map(|fun| &fun.name))
+                .find(|x| x.data == fun.name.data);
+
+            if let Some(previous) = previous {
+                return Result(Err(Error {
+                    span: fun.name.span,
+                    message: format!("duplicating members: `{}` at {:?}...", previous.data, previous.span.start),
+                    clarifying: format!("...and now `{}` at {:?}", fun.name.data, fun.name.span.start)
+                }))
+            }
+        }
+
+        Result(Ok(()))
+    }
+
+    fn check_variants(variants: &[Variant <'code>]) -> Result <()> {
+        for (idx, variant) in variants.iter().enumerate() {
+            if let Some(previous) = variants[..idx].iter().find(|x| x.name.data == variant.name.data) {
+                return Result(Err(Error {
+                    span: variant.name.span,
+                    message: format!("duplicating variants: `{}` at {:?}...", previous.name.data, previous.name.span.start),
+                    clarifying: format!("...and now `{}` at {:?}", variant.name.data, variant.name.span.start)
+                }))
+            }
+        }
+
+        Result(Ok(()))
     }
 }
 
-/// Pointer to the type definition(i.e. "ty TYPE = ...")
+/// Pointer to the type definition(i.e. "ty TYPE = ..." or "enum TYPE = ...")
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub struct RawTypeDefinitionIndex {
     idx: usize
 }
 
+impl RawTypeDefinitionIndex {
+    /// Returns the index of the defined type in the type bases container
+    pub const fn type_base_index(&self) -> u32 {
+        self.idx as u32
+    }
+}
+
 impl ParseDebug for RawTypeDefinitionIndex {
     fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
         let i = self.idx as usize;
@@ -83,20 +306,22 @@ impl ParseDebug for RawTypeDefinitionIndex {
 
 impl <'code> Parse <'code> for RawTypeDefinitionIndex {
     fn parse_impl(input: &mut ParseInput <'code>) -> Result <Self> {
-        let def = RawTypeDefinition::parse(input)?;
+        // Not `parse`, so that the progress made before an error is kept for choosing the error to report
+        let mut def = RawTypeDefinition::parse_impl(input)?;
+
+        let functions = core::mem::take(&mut def.functions);
+        let params = def.params.clone();
 
         let idx = match &mut input.type_bases {
             TypeBaseContainer::Raw(raw) => if let Some((idx, base)) = raw
                 .iter_mut()
                 .enumerate()
-                .find(|(_, x)| x.name() == def.name) {
+                .find(|(_, x)| x.name() == def.name && x.module() == Some(def.module)) {
                 if let RawTypeBase::Backed(base) = base {
                     return Result(Err(Error {
                         span: def.name.span,
                         message: format!("duplicating type definitions: `{}` at {:?}...", base.name.data, base.name.span.start),
-                        clarifying: format!("...and now `{}` at {:?}", def.name.data, def.name.span.start),
-                        filename: input.filename.to_string(),
-                        code: input.code.to_string()
+                        clarifying: format!("...and now `{}` at {:?}", def.name.data, def.name.span.start)
                     }))
                 } else {
                     *base = RawTypeBase::Backed(def);
@@ -110,6 +335,30 @@ impl <'code> Parse <'code> for RawTypeDefinitionIndex {
             _ => unreachable!()
         };
 
+        // The type the methods are called on, it is given to them as the first argument `i`
+        let receiver = if params.is_empty() {
+            TypeIndex::Scalar(idx as u32)
+        } else {
+            TypeIndex::Applied(idx as u32, params.iter().map(|param| TypeIndex::Generic(*param)).collect())
+        };
+
+        for mut fun in functions {
+            fun.owner = Some(idx as u32);
+
+            if fun.is_method {
+                fun.args.insert(0, TypedVariable {
+                    name: Spanned {
+                        data: "i",
+                        span: fun.name.span
+                    },
+                    doc: Doc::EMPTY,
+                    ty: receiver.clone()
+                })
+            }
+
+            input.top_level_items.push(Item::Fn(fun))
+        }
+
         Result(Ok(Self {
             idx
         }))
@@ -1 +1 @@
-crate::modules!(builtin definition raw_type_base type_index baked_type_base container);
+crate::modules!(builtin definition raw_type_base type_index baked_type_base container variant);
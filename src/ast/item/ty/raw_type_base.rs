@@ -4,23 +4,36 @@ use core::fmt::{Formatter, Write, Result as FmtResult};
 #[repr(u8)]
 #[derive(Clone)]
 pub enum RawTypeBase <'code> {
-    Stub(Spanned <&'code str>),
+    /// A type used before its definition, or with no definition at all like the builtin ones
+    Stub {
+        name: Spanned <&'code str>,
+
+        /// The module defining the type, `None` if no module does
+        module: Option <u32>
+    },
     Backed(RawTypeDefinition <'code>)
 }
 
 impl <'code> RawTypeBase <'code> {
     pub const fn name(&self) -> Spanned <&'code str> {
         match self {
-            Self::Stub(name) => *name,
+            Self::Stub { name, .. } => *name,
             Self::Backed(RawTypeDefinition { name, .. }) => *name
         }
     }
+
+    pub const fn module(&self) -> Option <u32> {
+        match self {
+            Self::Stub { module, .. } => *module,
+            Self::Backed(RawTypeDefinition { module, .. }) => Some(*module)
+        }
+    }
 }
 
 impl <'code> ParseDebug for RawTypeBase <'code> {
     fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
         match self {
-            Self::Stub(name) => f.write_fmt(format_args!("RawTypeBaseBackedWithADefinition::Stub({name:?})")),
+            Self::Stub { name, .. } => f.write_fmt(format_args!("RawTypeBaseBackedWithADefinition::Stub({name:?})")),
             Self::Backed(def) => {
                 f.write_str("RawTypeBaseBackedWithADefinition::Backed(")?;
                 def.debug_impl(input, f)?;
@@ -1,5 +1,5 @@
 use crate::*;
-use core::fmt::{Display, Formatter, Result as FmtResult};
+use core::fmt::{Display, Formatter, Write, Result as FmtResult};
 
 ///
 /// Pointer to the type(either raw or baked)
@@ -12,7 +12,18 @@ pub enum TypeIndex {
     Scalar(u32),
 
     /// A tuple type -- `(i32, bool)`, `(myi32,)`, `(aType, bType, (cType, dType))`
-    Tuple(Vec <TypeIndex>)
+    Tuple(Vec <TypeIndex>),
+
+    /// A type parameter of a generic item -- `T` in `enum Option <T>`
+    ///
+    /// The value in it represents an index of the parameter in `ParseInput.type_params`
+    Generic(u32),
+
+    /// A generic type with its type arguments -- `Option <T>`, `Pair <i32, (bool, T)>`
+    ///
+    /// Once all the arguments are known it is replaced by the `Scalar` pointing to
+    /// the instance of the type for them, so it is left only where the arguments depend on type parameters
+    Applied(u32, Vec <TypeIndex>)
 }
 
 impl TypeIndex {
@@ -34,18 +45,43 @@ impl TypeIndex {
 
     /// # Safety
     /// Call only after the baking of all the types
-    pub fn perform_binary_operation(&self, input: &ParseInput, op: BinaryOperator, operand: &TypeIndex) -> Option <TypeIndex> {
+    pub fn perform_binary_operation(&self, input: &mut ParseInput, op: BinaryOperator, operand: &TypeIndex) -> Option <TypeIndex> {
         let operand = if let BakedTypeBaseKind::Builtin(idx) = operand.baked_scalar(input)?.kind {
             idx
         } else {
             return None
         };
 
-        if let BakedTypeBaseKind::Builtin(idx) = self.baked_scalar(input)?.kind {
-            BUILTIN_BAKED_TYPES[idx].binary_operations.iter().find(|o| o.op == op && idx == operand).map(|_| self.clone())
+        let operation = if let BakedTypeBaseKind::Builtin(idx) = self.baked_scalar(input)?.kind {
+            BUILTIN_BAKED_TYPES[idx].binary_operations.iter().find(|o| o.op == op && idx == operand)?
         } else {
-            None
-        }
+            return None
+        };
+
+        Some(match operation.result {
+            Some(result) => Self::builtin(input, result),
+            None => self.clone()
+        })
+    }
+
+    /// Returns the pointer to the builtin type named `name`,
+    /// adding its base to the baked ones if it is not used anywhere yet
+    ///
+    /// Call only after the types are baked
+    pub fn builtin(input: &mut ParseInput, name: &str) -> TypeIndex {
+        let builtin = BUILTIN_BAKED_TYPES.iter().position(|ty| ty.base.name.data == name).expect("no such builtin type");
+
+        Self::Scalar(match &mut input.type_bases {
+            TypeBaseContainer::Baked(baked) => if let Some(idx) = baked
+                .iter()
+                .position(|x| matches!(x.kind, BakedTypeBaseKind::Builtin(idx) if idx == builtin)) {
+                idx
+            } else {
+                baked.push(BUILTIN_BAKED_TYPES[builtin].base.clone());
+                baked.len() - 1
+            },
+            _ => unreachable!("builtin types are looked up only after the types are baked")
+        } as u32)
     }
 
     pub fn baked_scalar <'a> (&'a self, input: &'a ParseInput) -> Option <&'a BakedTypeBase> {
@@ -57,6 +93,230 @@ impl TypeIndex {
             _ => unimplemented!()
         })
     }
+
+    /// Returns whether there are no type parameters in the type
+    pub fn is_concrete(&self) -> bool {
+        match self {
+            Self::Scalar(_) => true,
+            Self::Generic(_) => false,
+            Self::Tuple(types) | Self::Applied(_, types) => types.iter().all(Self::is_concrete)
+        }
+    }
+
+    /// Returns the number of types the type consists of, including itself
+    pub fn length(&self) -> usize {
+        match self {
+            Self::Scalar(_) | Self::Generic(_) => 1,
+            Self::Tuple(types) | Self::Applied(_, types) => 1 + types.iter().map(Self::length).sum::<usize>()
+        }
+    }
+
+    ///
+    /// Returns the index of the type base the type is built from together with its type arguments
+    ///
+    /// The arguments are empty for the instances of generic types, since they are already substituted into them
+    ///
+    pub fn base(&self) -> Option <(u32, &[TypeIndex])> {
+        match self {
+            Self::Scalar(idx) => Some((*idx, &[])),
+            Self::Applied(idx, args) => Some((*idx, args)),
+            _ => None
+        }
+    }
+
+    ///
+    /// Replaces the type parameters `params` with the corresponding `args`,
+    /// the parameters with no corresponding argument are left as is
+    ///
+    /// The result is not made concrete, see [`Self::substitute`] for that
+    ///
+    pub fn replace_params(&self, params: &[u32], args: &[TypeIndex]) -> TypeIndex {
+        match self {
+            Self::Scalar(_) => self.clone(),
+            Self::Generic(param) => match params.iter().position(|x| x == param).and_then(|idx| args.get(idx)) {
+                Some(arg) => arg.clone(),
+                None => self.clone()
+            },
+            Self::Tuple(types) => Self::Tuple(types.iter().map(|ty| ty.replace_params(params, args)).collect()),
+            Self::Applied(base, types) => Self::Applied(*base, types.iter().map(|ty| ty.replace_params(params, args)).collect())
+        }
+    }
+
+    ///
+    /// Replaces the generic types applied to concrete arguments with their instances,
+    /// instantiating the ones not used anywhere yet
+    ///
+    /// Call only after the types are baked
+    pub fn concretize(&self, input: &mut ParseInput) -> TypeIndex {
+        match self {
+            Self::Scalar(_) | Self::Generic(_) => self.clone(),
+            Self::Tuple(types) => Self::Tuple(types.iter().map(|ty| ty.concretize(input)).collect()),
+            Self::Applied(base, types) => {
+                let types = types.iter().map(|ty| ty.concretize(input)).collect::<Vec <_>>();
+
+                if types.iter().all(Self::is_concrete) {
+                    Self::Scalar(input.instantiate_type(*base, types))
+                } else {
+                    Self::Applied(*base, types)
+                }
+            }
+        }
+    }
+
+    ///
+    /// Replaces the type parameters `params` with the corresponding `args`
+    /// and makes the result concrete where possible
+    ///
+    /// Call only after the types are baked
+    pub fn substitute(&self, input: &mut ParseInput, params: &[u32], args: &[TypeIndex]) -> TypeIndex {
+        self.replace_params(params, args).concretize(input)
+    }
+
+    ///
+    /// Checks whether a value of the type `got` fits where a value of this type is expected,
+    /// inferring the type parameters `params` this type has: the types they stand for
+    /// are put into the corresponding places of `inferred`
+    ///
+    /// Call only after the types are baked
+    pub fn infer(&self, input: &ParseInput, got: &TypeIndex, params: &[u32], inferred: &mut [Option <TypeIndex>]) -> bool {
+        match (self, got) {
+            (Self::Generic(param), got) => match params.iter().position(|x| x == param) {
+                Some(idx) => match &inferred[idx] {
+                    Some(ty) => ty == got,
+                    None => {
+                        inferred[idx] = Some(got.clone());
+                        true
+                    }
+                },
+                None => self == got
+            },
+            (Self::Tuple(expected), Self::Tuple(got)) => expected.len() == got.len() && expected
+                .iter()
+                .zip(got)
+                .all(|(expected, got)| expected.infer(input, got, params, inferred)),
+            (Self::Applied(base, expected), got) => {
+                let got = match got {
+                    Self::Applied(got_base, got) if got_base == base => got,
+                    Self::Scalar(idx) => match &baked(input)[*idx as usize].instance_of {
+                        Some((got_base, got)) if got_base == base => got,
+                        _ => return false
+                    },
+                    _ => return false
+                };
+
+                expected.iter().zip(got).all(|(expected, got)| expected.infer(input, got, params, inferred))
+            },
+            _ => self == got
+        }
+    }
+
+    ///
+    /// Checks that every generic type in the type is given exactly as many type arguments
+    /// as it has parameters, `span` is where the type is written
+    ///
+    /// Call only after the types are baked
+    pub fn check_type_args(&self, input: &ParseInput, span: Span) -> Result <()> {
+        let (idx, args) = match self {
+            Self::Generic(_) => return Result(Ok(())),
+            Self::Tuple(types) => {
+                for ty in types {
+                    ty.check_type_args(input, span)?
+                }
+                return Result(Ok(()))
+            },
+            Self::Scalar(idx) => (*idx, &[][..]),
+            Self::Applied(idx, args) => (*idx, &args[..])
+        };
+
+        let base = &baked(input)[idx as usize];
+
+        // The instances already have their type arguments
+        if base.instance_of.is_none() && base.params.len() != args.len() {
+            let name = base.name.data;
+
+            let (message, clarifying) = if base.params.is_empty() {
+                (format!("the type `{name}` takes no type arguments"), format!("got `{}`", args.len()))
+            } else if args.is_empty() {
+                let params = base.params.iter().map(|param| input.type_params[*param as usize].data).collect::<Vec <_>>();
+                (format!("the type `{name}` needs type arguments"), format!("help: write them after the name, like `{name} <{}>`", params.join(", ")))
+            } else {
+                (format!("wrong number of type arguments for `{name}`"), format!("expected `{}`, got `{}`", base.params.len(), args.len()))
+            };
+
+            return Result(Err(Error {
+                span,
+                message,
+                clarifying
+            }))
+        }
+
+        for arg in args {
+            arg.check_type_args(input, span)?
+        }
+
+        Result(Ok(()))
+    }
+
+    /// Writes the type arguments the way they are written after a generic type -- ` <i32, T>`
+    pub fn debug_args(args: &[TypeIndex], input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
+        f.write_str(" <")?;
+        for (idx, arg) in args.iter().enumerate() {
+            if idx != 0 {
+                f.write_str(", ")?;
+            }
+            arg.debug_impl(input, f)?;
+        }
+        f.write_char('>')
+    }
+
+    /// Finds the type named `name` of the `module` among the baked ones, for the types written in the bodies of functions
+    fn find_baked(input: &mut ParseInput, module: Option <u32>, name: Spanned <&str>) -> Result <u32> {
+        if module.is_none() && BUILTIN_BAKED_TYPES.iter().any(|ty| ty.base.name.data == name.data) {
+            return match Self::builtin(input, name.data) {
+                Self::Scalar(idx) => Result(Ok(idx)),
+                _ => unreachable!()
+            }
+        }
+
+        match input.find_type_in(module, name.data) {
+            Some(idx) => Result(Ok(idx)),
+            None => Result(Err(Error {
+                span: name.span,
+                message: format!("the type `{}` has no definition", name.data),
+                clarifying: String::from("a ghostly type")
+            }))
+        }
+    }
+
+    /// Parses the rest of the type `module::Type` after `module::`, returning the module and the name of the type
+    fn parse_qualified <'code> (input: &mut ParseInput <'code>, module: Spanned <&'code str>) -> Result <(u32, Spanned <&'code str>)> {
+        let error = |span: Span, message: String, clarifying: String| Result(Err(Error {
+            span,
+            message,
+            clarifying
+        }));
+
+        let idx = match input.imported_module(module.data) {
+            Some(idx) => idx,
+            None => return error(module.span, format!("no module named `{}` is imported", module.data), String::from("help: import it with `use`"))
+        };
+
+        let name = input.ident_as_spanned_str()?;
+
+        if !input.modules[idx as usize].types.contains(&name.data) {
+            let path = SourceFile::display_path(input.modules[idx as usize].path);
+            return error(name.span, format!("no type named `{}` in the module `{path}`", name.data), String::from("here"))
+        }
+
+        Result(Ok((idx, name)))
+    }
+}
+
+fn baked <'a, 'code> (input: &'a ParseInput <'code>) -> &'a [BakedTypeBase <'code>] {
+    match &input.type_bases {
+        TypeBaseContainer::Baked(baked) => baked,
+        _ => unreachable!()
+    }
 }
 
 impl <'code> Parse <'code> for TypeIndex {
@@ -70,7 +330,30 @@ impl <'code> Parse <'code> for TypeIndex {
         // Fallback to scalars
         let name = input.ident_as_spanned_str()?;
 
-        let base_index = input.find_or_add_raw_type_base(name);
+        // `module::Type`
+        let (module, name) = if input.colon_colon().0.is_ok() {
+            let (module, name) = Self::parse_qualified(input, name)?;
+            (Some(module), name)
+        } else if let Some(param) = input.type_params_in_scope.iter().find(|param| input.type_params[**param as usize].data == name.data) {
+            return Result(Ok(Self::Generic(*param)))
+        } else {
+            (input.type_module(name.data), name)
+        };
+
+        let base_index = if input.is_type_base_container_raw() {
+            input.find_or_add_raw_type_base(module, name)
+        } else {
+            Self::find_baked(input, module, name)?
+        };
+
+        // Type arguments of a generic type
+        if input.less().0.is_ok() {
+            let args = Punctuated::<_, "">::new(input, ParseInput::comma, ParseInput::greater)?;
+
+            if !args.vec.is_empty() {
+                return Result(Ok(Self::Applied(base_index, args.vec)))
+            }
+        }
 
         Result(Ok(Self::Scalar(base_index)))
     }
@@ -81,7 +364,18 @@ impl ParseDebug for TypeIndex {
         match self {
             Self::Scalar(idx) => match &input.type_bases {
                 TypeBaseContainer::Raw(raw) => Display::fmt(&raw[*idx as usize].name().data, f),
-                TypeBaseContainer::Baked(baked) => Display::fmt(&baked[*idx as usize].name.data, f)
+                TypeBaseContainer::Baked(baked) => {
+                    Display::fmt(&baked[*idx as usize].name.data, f)?;
+                    match &baked[*idx as usize].instance_of {
+                        Some((_, args)) => Self::debug_args(args, input, f),
+                        None => Ok(())
+                    }
+                }
+            },
+            Self::Generic(param) => Display::fmt(&input.type_params[*param as usize].data, f),
+            Self::Applied(base, args) => {
+                Self::Scalar(*base).debug_impl(input, f)?;
+                Self::debug_args(args, input, f)
             },
             Self::Tuple(tuple) => if !tuple.is_empty() {
                 let mut builder = f.debug_tuple("");
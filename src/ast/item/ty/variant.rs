@@ -0,0 +1,47 @@
+use crate::*;
+use core::fmt::{Debug, Formatter, Write, Result as FmtResult};
+
+///
+/// A variant of an enum -- its name and the types of the values it holds
+///
+/// Examples: `None`, `Some i32`, `Pair i32 (bool, bool)`
+///
+#[derive(Clone)]
+pub struct Variant <'code> {
+    pub name: Spanned <&'code str>,
+
+    /// The doc comments written before the variant
+    pub doc: Doc <'code>,
+    pub payload: Vec <TypeIndex>
+}
+
+impl <'code> ParseDebug for Variant <'code> {
+    fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
+        self.name.fmt(f)?;
+
+        for ty in &self.payload {
+            f.write_char(' ')?;
+            ty.debug_impl(input, f)?
+        }
+
+        Ok(())
+    }
+}
+
+impl <'code> Parse <'code> for Variant <'code> {
+    fn parse_impl(input: &mut ParseInput <'code>) -> Result <Self> {
+        let name = input.ident_as_spanned_str()?;
+
+        let mut payload = vec![];
+
+        while !input.is_exhausted() && !check_if_the_next_token_is_newline(input) {
+            payload.push(TypeIndex::parse(input)?)
+        }
+
+        Result(Ok(Self {
+            name,
+            doc: Doc::EMPTY,
+            payload
+        }))
+    }
+}
@@ -1 +1 @@
-crate::modules!(typed_variable parse punctuated item expr context tls);
+crate::modules!(typed_variable doc parse punctuated item expr context module tls);
@@ -0,0 +1,228 @@
+use crate::*;
+
+///
+/// A source file of the program, the root one or a module it uses
+///
+/// The module `a::b` is the file `a/b.oko` in the directory of the root file
+///
+#[derive(Clone)]
+pub struct SourceFile {
+    /// The path of the module, empty for the root file
+    pub path: Vec <String>,
+    pub filename: String,
+
+    /// The code prepared for tokenizing
+    pub code: String,
+
+    /// The id of the file in the source map, the spans of its tokens point there
+    pub file: FileId,
+
+    /// What one level of the indentation of the code is
+    pub indent: IndentUnit
+}
+
+impl SourceFile {
+    /// The extension of the files of modules
+    pub const EXTENSION: &'static str = "oko";
+
+    /// Adds the file with the prepared `code` to the `source_map`, its indentation unit is detected
+    pub fn new(source_map: &mut SourceMap, path: Vec <String>, filename: String, code: String) -> Self {
+        let file = source_map.add(&filename, &code);
+
+        Self {
+            path,
+            filename,
+            code,
+            file,
+            indent: IndentUnit::Detect
+        }
+    }
+
+    /// Returns the path of the module the way it is written in the code -- `a::b`
+    pub fn display_path(path: &[String]) -> String {
+        path.join("::")
+    }
+}
+
+///
+/// A tokenized source file and the names it brings into scope
+///
+/// The items of all the modules are in the same `ParseInput.top_level_items`,
+/// each of them knows the index of its module in `ParseInput.modules`
+///
+pub struct Module <'code> {
+    pub path: &'code [String],
+    pub filename: &'code str,
+    pub file: FileId,
+    pub tokens: &'code [Token <'code>],
+
+    /// The names of the types defined in the module, collected before any item is parsed,
+    /// so that the types of other modules can be told from the builtin ones
+    pub types: Vec <&'code str>,
+    pub imports: Vec <Import <'code>>
+}
+
+///
+/// A name brought into a module by `use`
+///
+pub struct Import <'code> {
+    pub name: Spanned <&'code str>,
+    pub target: ImportTarget <'code>
+}
+
+#[derive(Copy, Clone)]
+pub enum ImportTarget <'code> {
+    /// The module with this index, its items are used as `module::item` then
+    Module(u32),
+
+    /// The type or the function of the same name in the module with this index
+    Item(u32),
+
+    /// The variant or the function of the same name in the type named so in the module with this index
+    Member(u32, Spanned <&'code str>)
+}
+
+impl <'code> Import <'code> {
+    ///
+    /// Parses the line `use path::Name1 Name2`, which brings the items `Name1` and `Name2` of `path` into scope
+    ///
+    /// The `path` starts with the longest module path there is, and the rest of it is the name of a type,
+    /// whose variants and functions are imported then. With no module in the path the current module is meant,
+    /// which makes `use Enum::Variant` work
+    ///
+    pub fn parse_line(input: &mut ParseInput <'code>) -> Result <Vec <Self>> {
+        input.keyword("use")?;
+
+        let mut segments = vec![input.ident_as_spanned_str()?];
+
+        while input.colon_colon().0.is_ok() {
+            segments.push(input.ident_as_spanned_str()?)
+        }
+
+        // The last segment is the first of the imported names
+        let mut names = vec![segments.pop().unwrap()];
+
+        while !input.is_exhausted() && !check_if_the_next_token_is_newline(input) {
+            names.push(input.ident_as_spanned_str()?)
+        }
+
+        let strings = segments.iter().map(|segment| segment.data.to_string()).collect::<Vec <_>>();
+
+        let (module, len) = (1..=segments.len())
+            .rev()
+            .find_map(|len| input.find_module(&strings[..len]).map(|module| (module, len)))
+            .unwrap_or((input.module, 0));
+
+        Result(names.into_iter().map(|name| {
+            let mut path = strings.clone();
+            path.push(name.data.to_string());
+
+            // The name is a module in a directory even if there is no file for the directory itself
+            let target = match (input.find_module(&path), &segments[len..]) {
+                (Some(submodule), _) => ImportTarget::Module(submodule),
+                (None, []) => ImportTarget::Item(module),
+                (None, [ty]) => ImportTarget::Member(module, *ty),
+                (None, [first, ..]) => return Err(Error {
+                    span: first.span,
+                    message: format!("no module named `{}`", SourceFile::display_path(&strings[..=len])),
+                    clarifying: format!("there is no file `{}`", strings[..=len].join("/") + "." + SourceFile::EXTENSION)
+                })
+            };
+
+            Ok(Self {
+                name,
+                target
+            })
+        }).collect())
+    }
+}
+
+impl <'code> ParseInput <'code> {
+    /// Makes the module with the index `module` the current one, so that its names are in scope
+    pub fn enter_module(&mut self, module: u32) {
+        self.module = module
+    }
+
+    /// Returns the id of the file of the current module
+    pub fn file(&self) -> FileId {
+        self.modules[self.module as usize].file
+    }
+
+    /// Returns the index of the module with the `path`
+    pub fn find_module(&self, path: &[String]) -> Option <u32> {
+        self.modules.iter().position(|module| module.path == path).map(|idx| idx as u32)
+    }
+
+    /// Returns the names the current module imports
+    pub fn imports(&self) -> &[Import <'code>] {
+        &self.modules[self.module as usize].imports
+    }
+
+    /// Returns the module imported into the current one as `name`
+    pub fn imported_module(&self, name: &str) -> Option <u32> {
+        self.imports().iter().find_map(|import| match import.target {
+            ImportTarget::Module(module) if import.name.data == name => Some(module),
+            _ => None
+        })
+    }
+
+    ///
+    /// Returns the module defining the type named `name` that is in scope of the current module,
+    /// `None` if there is no such one, like for the builtin types
+    ///
+    pub fn type_module(&self, name: &str) -> Option <u32> {
+        if self.modules[self.module as usize].types.contains(&name) {
+            return Some(self.module)
+        }
+
+        self.imports().iter().find_map(|import| match import.target {
+            ImportTarget::Item(module) if import.name.data == name && self.modules[module as usize].types.contains(&name) => Some(module),
+            _ => None
+        })
+    }
+
+    /// Finds the baked type named `name` defined in the `module`, or the builtin one if `module` is `None`
+    pub fn find_type_in(&self, module: Option <u32>, name: &str) -> Option <u32> {
+        match &self.type_bases {
+            TypeBaseContainer::Baked(baked) => baked
+                .iter()
+                .position(|base| base.instance_of.is_none() && base.module == module && base.name.data == name)
+                .map(|idx| idx as u32),
+            _ => unreachable!()
+        }
+    }
+
+    /// Finds the baked type named `name` that is in scope of the current module
+    pub fn find_type(&self, name: &str) -> Option <u32> {
+        self.find_type_in(self.type_module(name), name)
+    }
+
+    /// Returns whether the function `fun` can be called by its name alone in the current module
+    pub fn is_fn_in_scope(&self, fun: &Fn) -> bool {
+        match fun.owner {
+            None if fun.module == self.module => true,
+            owner => self.imports().iter().any(|import| import.name == fun.name && match (import.target, owner) {
+                (ImportTarget::Item(module), None) => module == fun.module,
+                (ImportTarget::Member(module, ty), Some(owner)) => self.find_type_in(Some(module), ty.data) == Some(owner),
+                _ => false
+            })
+        }
+    }
+
+    /// Returns whether the variant named `name` of the enum `ty` can be used without the name of the enum in the current module
+    pub fn is_variant_in_scope(&self, ty: u32, name: &str) -> bool {
+        let base = match &self.type_bases {
+            TypeBaseContainer::Baked(baked) => &baked[ty as usize],
+            _ => unreachable!()
+        };
+
+        if self.find_type(base.name.data) == Some(ty) {
+            return true
+        }
+
+        self.imports().iter().any(|import| matches!(
+            import.target,
+            ImportTarget::Member(module, enum_name) if import.name.data == name && enum_name.data == base.name.data && base.module == Some(module)
+        ))
+    }
+}
@@ -1,5 +1,4 @@
 use core::fmt::{Debug, Formatter, Result as FmtResult};
-use core::ptr::NonNull;
 use crate::*;
 
 pub trait ParseDebug: Sized {
@@ -28,11 +27,30 @@ impl <'input, 'code, T: ParseDebug> Debug for ParseDebugHelper <'input, 'code, T
 
 pub struct ParseInput <'code> {
     pub stream: TokenStream <'code>,
-    pub code: &'code str,
-    pub filename: &'code str,
+
+    /// All the source files of the program, the root one goes first
+    pub modules: Vec <Module <'code>>,
+
+    /// The code of the files the spans point into
+    pub source_map: &'code SourceMap,
+
+    /// The index of the module being parsed or baked in `modules`
+    pub module: u32,
     pub type_bases: TypeBaseContainer <'code>,
     pub fn_body_bases: FnBodyContainer <'code>,
-    pub top_level_items: Vec <Item <'code>>
+    pub top_level_items: Vec <Item <'code>>,
+
+    /// The type parameters of all the generic items, `TypeIndex::Generic` points here
+    pub type_params: Vec <Spanned <&'code str>>,
+
+    /// The type parameters of the item being parsed, the names of the types parsed now are looked for among them first
+    pub type_params_in_scope: Vec <u32>,
+
+    /// The instances of generic functions requested by the bodies baked so far
+    pub fn_instances: FnInstances,
+
+    /// The errors that did not stop the current stage and the warnings, reported together after it
+    pub diagnostics: Vec <Diagnostic>
 }
 
 impl <'code> Debug for ParseInput <'code> {
@@ -51,8 +69,6 @@ impl <'code> Debug for ParseInput <'code> {
 
         f.debug_struct("ParseInput")
             .field("stream", &self.stream)
-            .field("code", &self.code)
-            .field("filename", &self.filename)
             .field("type_bases", &self.type_bases.debug(self))
             .field("fn_body_bases", &self.fn_body_bases.debug(self))
             .field("top_level_items", &Inner { input: self })
@@ -62,33 +78,55 @@ impl <'code> Debug for ParseInput <'code> {
 
 impl <'code> ParseInput <'code> {
     pub fn ident_as_spanned_str(&mut self) -> Result <Spanned <&'code str>> {
-        // SAFETY: ident returns an identifier which has its str
-        Result(Ok(unsafe { self.ident()?.to_spanned_str().unwrap_unchecked() }))
+        // ident returns an identifier which has its str
+        Result(Ok(self.ident()?.to_spanned_str().unwrap()))
+    }
+
+    pub fn int_as_spanned_str(&mut self) -> Result <Spanned <&'code str>> {
+        // int returns an integer literal which has its str
+        Result(Ok(self.int()?.to_spanned_str().unwrap()))
     }
 
-    pub fn keyword(&mut self, keyword: &str) -> Result <()> {
+    /// Returns the span of the keyword on success
+    pub fn keyword(&mut self, keyword: &str) -> Result <Span> {
         let next = self.ident().with_custom_err_message(|| format!("expected a keyword `{keyword}`..."))?;
 
-        if let Token { kind: TokenKind::Ident(ident), .. } = next {
+        if let Token { kind: TokenKind::Ident(ident), span } = next {
             if *ident == keyword {
-                return Result(Ok(()))
+                return Result(Ok(*span))
             }
         } else {
             unreachable!()
         }
 
-        let next = next as *const Token <'code>;
-        let next = unsafe { core::mem::transmute(&core::ptr::read(next)) };
         self.generate_expected_err(&format!("a keyword `{keyword}`"), next)
     }
 
+    /// Remembers the diagnostic to report it together with all the others after the current stage
+    pub fn report(&mut self, diagnostic: impl Into <Diagnostic>) {
+        self.diagnostics.push(diagnostic.into())
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+
+    /// Returns the diagnostics reported so far, sorted by the place they are about
+    pub fn take_diagnostics(&mut self) -> Vec <Diagnostic> {
+        let mut diagnostics = core::mem::take(&mut self.diagnostics);
+        diagnostics.sort_by_key(|diagnostic| (
+            diagnostic.span.file,
+            diagnostic.span.start.line,
+            diagnostic.span.start.column
+        ));
+        diagnostics
+    }
+
     pub fn generate_expected_err <T> (&self, message: &str, next: &Token) -> Result <T> {
         Result(Err(Error {
             span: next.span,
             message: format!("expected {message}..."),
-            clarifying: format!("...but got {next:?}"),
-            filename: self.filename.to_string(),
-            code: self.code.to_string()
+            clarifying: format!("...but got {next:?}")
         }))
     }
 
@@ -96,23 +134,103 @@ impl <'code> ParseInput <'code> {
         matches!(&self.type_bases, TypeBaseContainer::Raw(_))
     }
 
-    pub fn find_or_add_raw_type_base(&mut self, name: Spanned <&'code str>) -> u32 {
+    ///
+    /// Returns the index of the raw type named `name` defined in the `module`,
+    /// adding a stub for it if it is not parsed yet
+    ///
+    /// The types with no `module` are the builtin ones or the ones with no definition
+    ///
+    pub fn find_or_add_raw_type_base(&mut self, module: Option <u32>, name: Spanned <&'code str>) -> u32 {
         (match &mut self.type_bases {
             TypeBaseContainer::Raw(raw) => if let Some(idx) = raw
                 .iter()
                 .enumerate()
-                .find(|(_, x)| x.name() == name)
+                .find(|(_, x)| x.name() == name && x.module() == module)
                 .map(|(x, _)| x) {
                 idx
             } else {
                 let idx = raw.len();
-                raw.push(RawTypeBase::Stub(name));
+                raw.push(RawTypeBase::Stub {
+                    name,
+                    module
+                });
                 idx
             },
             _ => unreachable!()
         }) as u32
     }
 
+    ///
+    /// Returns the index of the instance of the generic type `base` for the concrete type arguments `args`,
+    /// baking it if there is none yet
+    ///
+    /// Call only after the types are baked
+    pub fn instantiate_type(&mut self, base: u32, args: Vec <TypeIndex>) -> u32 {
+        let baked = match &mut self.type_bases {
+            TypeBaseContainer::Baked(baked) => baked,
+            _ => unreachable!()
+        };
+
+        if let Some(idx) = baked.iter().position(|x| matches!(&x.instance_of, Some((of, of_args)) if *of == base && *of_args == args)) {
+            return idx as u32
+        }
+
+        let generic = baked[base as usize].clone();
+        let idx = baked.len();
+
+        // Added before the substitution, so that the type can contain itself
+        baked.push(BakedTypeBase {
+            kind: BakedTypeBaseKind::Builtin(0),
+            name: generic.name,
+            doc: generic.doc.clone(),
+            module: generic.module,
+            params: vec![],
+            instance_of: Some((base, args.clone()))
+        });
+
+        let kind = generic.kind.substitute(self, &generic.params, &args);
+
+        match &mut self.type_bases {
+            TypeBaseContainer::Baked(baked) => baked[idx].kind = kind,
+            _ => unreachable!()
+        }
+
+        idx as u32
+    }
+
+    ///
+    /// Parses the type parameters of an item -- `<T, U>` after its name, if there are any,
+    /// and brings them into scope for the types parsed after that
+    ///
+    /// Returns the indices of the parameters in `type_params`
+    ///
+    pub fn type_params_declaration(&mut self) -> Result <Vec <u32>> {
+        self.type_params_in_scope.clear();
+
+        if self.less().0.is_err() {
+            return Result(Ok(vec![]))
+        }
+
+        let names = Punctuated::<_, "">::new_with_custom_parser(self, ParseInput::ident_as_spanned_str, ParseInput::comma, ParseInput::greater)?.vec;
+
+        for (idx, name) in names.iter().enumerate() {
+            if let Some(previous) = names[..idx].iter().find(|x| x.data == name.data) {
+                return Result(Err(Error {
+                    span: name.span,
+                    message: format!("duplicating type parameters: `{}` at {:?}...", previous.data, previous.span.start),
+                    clarifying: format!("...and now `{}` at {:?}", name.data, name.span.start)
+                }))
+            }
+        }
+
+        for name in names {
+            self.type_params_in_scope.push(self.type_params.len() as u32);
+            self.type_params.push(name)
+        }
+
+        Result(Ok(self.type_params_in_scope.clone()))
+    }
+
     pub fn add_raw_fn_body_base(&mut self, base: RawFnBodyBase <'code>) -> u32 {
         (match &mut self.fn_body_bases {
             FnBodyContainer::Raw(raw) => {
@@ -130,45 +248,70 @@ impl <'code> ParseInput <'code> {
     pub fn peek(&self, err_message: &str) -> Result <usize> {
         if self.is_exhausted() {
             return Result(Err(Error {
-                span: Span::extend_by_one(self.stream.buf.last().map(|x| x.span.end).unwrap_or(CursorPosition::DEFAULT)),
+                span: Span::extend_by_one(self.file(), self.stream.buf.last().map(|x| x.span.end).unwrap_or(CursorPosition::DEFAULT)),
                 message: err_message.to_string(),
-                clarifying: String::from("got <EOF>"),
-                filename: self.filename.to_string(),
-                code: self.code.to_string()
+                clarifying: String::from("got <EOF>")
             }))
         }
 
-        // SAFETY: out-of-bounds case checked above
         Result(Ok(self.stream.cur))
     }
 
     ///
     /// Finds the end of the current block and sets `self.cur` to the end of it
     ///
-    /// `self.cur` should point to the very first token of the block
+    /// `self.cur` should point to the beginning of the line after the one that opens the block,
+    /// the block is the lines after the [`TokenKind::Indent`] there up to the matching [`TokenKind::Dedent`],
+    /// both of which are skipped. Empty lines and doc comments before the block are skipped too.
+    /// The block is empty and `self.cur` is left as is if the line is not indented
     ///
-    /// `block_nesting_level` means the amount of tabs before each instruction in a block
+    /// Returns content of the block, which borrows the tokens of the file and not the input
     ///
-    /// Returns content of the block
-    ///
-    pub fn find_end_of_block_and_return_everything_in_it_and_also_go_forward_to_its_end(&mut self, block_nesting_level: u8) -> NonNull <[Token <'code>]> {
-        let mut tabs = 0;
+    pub fn find_end_of_block_and_return_everything_in_it_and_also_go_forward_to_its_end(&mut self) -> &'code [Token <'code>] {
+        let buf = self.stream.buf;
+        let cur = self.stream.cur;
+
+        remove_newlines_and_docs(self);
+
+        if self.indent().0.is_err() {
+            self.stream.cur = cur;
+            return &[]
+        }
 
-        for (idx, token) in self.stream.buf[self.stream.cur..].iter().enumerate() {
-            if token.kind == TokenKind::Tab {
-                tabs += 1;
-            } else if token.kind == TokenKind::Newline {
-                tabs = 0;
-            } else if tabs != block_nesting_level {
-                let result = unsafe { NonNull::new_unchecked(&self.stream.buf[self.stream.cur..self.stream.cur + idx] as *const [Token] as *mut _) };
-                self.stream.cur += idx;
-                return result
+        let start = self.stream.cur;
+        let mut depth = 1;
+
+        for (idx, token) in buf[start..].iter().enumerate() {
+            match token.kind {
+                TokenKind::Indent => depth += 1,
+                TokenKind::Dedent => depth -= 1,
+                _ => continue
+            }
+
+            if depth == 0 {
+                self.stream.cur = start + idx + 1;
+                return &buf[start..start + idx]
             }
         }
 
-        let result = unsafe { NonNull::new_unchecked(&self.stream.buf[self.stream.cur..] as *const [Token] as *mut _) };
-        self.stream.cur = self.stream.buf.len() - 1;
-        result
+        self.stream.cur = buf.len();
+        &buf[start..]
+    }
+
+    ///
+    /// Skips the rest of the current line and the block nested into it,
+    /// so that parsing can go on after an error
+    ///
+    pub fn skip_to_next_line(&mut self) {
+        while !self.is_exhausted() && self.stream.buf[self.stream.cur].kind != TokenKind::Newline {
+            self.go_forward()
+        }
+
+        if !self.is_exhausted() {
+            self.go_forward()
+        }
+
+        let _ = self.find_end_of_block_and_return_everything_in_it_and_also_go_forward_to_its_end();
     }
 
     #[inline(always)]
@@ -34,7 +34,7 @@ pub fn print_punctuated_seq <T: Debug, const S: &'static str> (mut iter: impl Do
     last.fmt(f)
 }
 
-pub type ParseFun <'code> = for <'a> fn(&'a mut ParseInput <'code>) -> Result <&'a Token <'code>>;
+pub type ParseFun <'code> = fn(&mut ParseInput <'code>) -> Result <&'code Token <'code>>;
 
 impl <'code, T, const S: &'static str> Punctuated <'code, T, S> {
     pub fn single(value: T) -> Self {
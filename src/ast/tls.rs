@@ -4,21 +4,24 @@ pub fn remove_newlines(input: &mut ParseInput) {
     general_remove(input, |kind| matches!(kind, TokenKind::Newline))
 }
 
-pub fn remove_newlines_and_tabs(input: &mut ParseInput) {
-    general_remove(input, |kind| matches!(kind, TokenKind::Newline | TokenKind::Tab))
+/// Skips the newlines and the doc comments, which are ignored where they document nothing
+pub fn remove_newlines_and_docs(input: &mut ParseInput) {
+    general_remove(input, |kind| matches!(kind, TokenKind::Newline | TokenKind::DocComment(_)))
 }
 
-pub unsafe fn check_if_the_next_token_is_newline(input: &mut ParseInput) -> bool {
+/// Checks whether the next token is a newline, `false` if there are no tokens left
+pub fn check_if_the_next_token_is_newline(input: &mut ParseInput) -> bool {
     check_if_the_next_token_is(input, |kind| matches!(kind, TokenKind::Newline))
 }
 
+/// Checks whether the next token is an identifier equal to one of `keywords`, `false` if there are no tokens left
+pub fn check_if_the_next_token_is_keyword(input: &mut ParseInput, keywords: &[&str]) -> bool {
+    check_if_the_next_token_is(input, |kind| matches!(kind, TokenKind::Ident(ident) if keywords.contains(ident)))
+}
+
 fn general_remove(input: &mut ParseInput, cond: impl for <'a, 'code> core::ops::Fn(&'a TokenKind <'code>) -> bool) {
     loop {
-        if input.is_exhausted() {
-            break
-        }
-
-        if unsafe { check_if_the_next_token_is(input, &cond) } {
+        if check_if_the_next_token_is(input, &cond) {
             input.go_forward()
         } else {
             break
@@ -26,7 +29,6 @@ fn general_remove(input: &mut ParseInput, cond: impl for <'a, 'code> core::ops::
     }
 }
 
-unsafe fn check_if_the_next_token_is(input: &mut ParseInput, cond: impl for <'a, 'code> core::ops::Fn(&'a TokenKind <'code>) -> bool) -> bool {
-    let next = input.stream.buf.get_unchecked(input.peek("").0.ok().unwrap_unchecked());
-    cond(&next.kind)
+fn check_if_the_next_token_is(input: &mut ParseInput, cond: impl for <'a, 'code> core::ops::Fn(&'a TokenKind <'code>) -> bool) -> bool {
+    input.stream.buf.get(input.stream.cur).is_some_and(|next| cond(&next.kind))
 }
@@ -4,6 +4,9 @@ use core::fmt::{Formatter, Result as FmtResult};
 #[derive(Clone)]
 pub struct TypedVariable <'code> {
     pub name: Spanned <&'code str>,
+
+    /// The doc comments written before the field of a `ty` type, empty for the other variables
+    pub doc: Doc <'code>,
     pub ty: TypeIndex
 }
 
@@ -28,8 +31,8 @@ impl <'code> Parse <'code> for TypedVariableWrap <'code> {
         let mut names = vec![input.ident_as_spanned_str()?];
 
         while let Result(Ok(ok)) = input.ident() {
-            // SAFETY: ident returns an identifier which has its str
-            names.push(unsafe { ok.to_spanned_str().unwrap_unchecked() })
+            // ident returns an identifier which has its str
+            names.push(ok.to_spanned_str().unwrap())
         }
 
         input.two_dots()?;
@@ -38,6 +41,7 @@ impl <'code> Parse <'code> for TypedVariableWrap <'code> {
 
         Result(Ok(Self(names.into_iter().map(|name| TypedVariable {
             name,
+            doc: Doc::EMPTY,
             ty: ty.clone()
         }).collect())))
     }
@@ -0,0 +1,868 @@
+use crate::*;
+use core::fmt::Write;
+use std::path::Path;
+
+/// Builtin types, the C types they are lowered to and the `printf` formats they are printed with
+///
+/// Empty format means the type has its own printer
+const C_BUILTIN_TYPES: &[(&str, &str, &str)] = &[
+    ("i8", "int8_t", "\"%\" PRId8"),
+    ("i16", "int16_t", "\"%\" PRId16"),
+    ("i32", "int32_t", "\"%\" PRId32"),
+    ("i64", "int64_t", "\"%\" PRId64"),
+    ("i128", "__int128", ""),
+    ("isize", "intptr_t", "\"%\" PRIdPTR"),
+    ("u8", "uint8_t", "\"%\" PRIu8"),
+    ("u16", "uint16_t", "\"%\" PRIu16"),
+    ("u32", "uint32_t", "\"%\" PRIu32"),
+    ("u64", "uint64_t", "\"%\" PRIu64"),
+    ("u128", "unsigned __int128", ""),
+    ("usize", "uintptr_t", "\"%\" PRIuPTR"),
+    ("f32", "float", "\"%g\""),
+    ("f64", "double", "\"%g\""),
+    ("bool", "bool", "")
+];
+
+const PRELUDE: &str = "\
+#include <inttypes.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+struct oko_unit {
+\tchar _;
+};
+
+#define OKO_UNIT ((struct oko_unit){0})
+";
+
+///
+/// Lowers the baked `input` into a C translation unit
+///
+/// If there is a `main` function with no arguments, the unit gets
+/// the C `main` that calls it and prints the returned value if it is not `()`
+///
+/// Arithmetic overflows stop the program the same way as in the interpreter,
+/// but stack overflows are not checked by the generated code
+///
+pub fn transpile_to_c(input: &ParseInput) -> String {
+    let mut backend = CBackend {
+        input,
+        structs: String::new(),
+        helpers: String::new(),
+        user_types: vec![],
+        tuples: vec![],
+        printers: vec![],
+        arith_helpers: vec![],
+        module: 0,
+        scope: vec![],
+        counter: 0,
+        indent: 0
+    };
+
+    let mut prototypes = String::new();
+    let mut functions = String::new();
+
+    for (idx, item) in input.top_level_items.iter().enumerate() {
+        // The generic functions are lowered through their instances
+        if let Item::Fn(fun) = item {
+            if fun.is_generic() {
+                continue
+            }
+
+            let signature = backend.signature(idx, fun);
+            let _ = writeln!(prototypes, "static {signature};");
+            functions += &backend.function(fun, &signature);
+        }
+    }
+
+    let mut entry = String::new();
+
+    if let Some(main) = input.top_level_items.iter().find_map(|item| match item {
+        Item::Fn(fun) if fun.name.data == "main" && fun.owner.is_none() && fun.module == 0 && fun.args.is_empty() && fun.type_params.is_empty() => Some(fun),
+        _ => None
+    }) {
+        entry += "int main(void) {\n";
+        if main.ret_ty.is_unit_tuple() {
+            entry += "\t(void)oko_fn_main();\n";
+        } else {
+            let ty = backend.c_type(&main.ret_ty);
+            let printer = backend.printer(&main.ret_ty);
+            let _ = writeln!(entry, "\t{ty} result = oko_fn_main();\n\t{printer}(result);\n\tputchar('\\n');");
+        }
+        entry += "\treturn 0;\n}\n";
+    }
+
+    // The runtime errors tell the file by the index of its module
+    let filenames = input.modules
+        .iter()
+        .map(|module| format!("\"{}\"", module.filename.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect::<Vec <_>>()
+        .join(", ");
+
+    // Only the checked arithmetic stops the program, the unit has no unused functions for `-Wall`
+    let panic = if backend.arith_helpers.is_empty() {
+        String::new()
+    } else {
+        format!("
+static const char *const oko_files[] = {{ {filenames} }};
+
+static void oko_panic(const char *message, unsigned file, int line, int column) {{
+\tfprintf(stderr, \"error: %s at %s:%d:%d\\n\", message, oko_files[file], line, column);
+\texit(101);
+}}
+")
+    };
+
+    format!("{PRELUDE}{panic}
+{}{}{prototypes}
+{functions}{entry}", backend.structs, backend.helpers)
+}
+
+/// Lowers the baked `input` into C and writes it into the file at `path`
+pub fn write_c(input: &ParseInput, path: impl AsRef <Path>) -> std::io::Result <()> {
+    std::fs::write(path, transpile_to_c(input))
+}
+
+struct CBackend <'input, 'code> {
+    input: &'input ParseInput <'code>,
+
+    /// Definitions of the structs, each goes after the ones it contains
+    structs: String,
+
+    /// Definitions of the division and printing functions
+    helpers: String,
+
+    /// Indices of the `ty` and `enum` types whose structs are already defined
+    user_types: Vec <u32>,
+
+    /// Tuple types whose structs are already defined, the index is the number in the name of the struct
+    tuples: Vec <TypeIndex>,
+
+    /// Types whose printers are already defined together with the names of the printers
+    printers: Vec <(TypeIndex, String)>,
+
+    /// Operations and builtin types whose checked arithmetic functions are already defined
+    arith_helpers: Vec <(&'static str, &'static str)>,
+
+    /// The module of the function being lowered
+    module: u32,
+
+    /// Variables visible at the moment -- their names, C names and C types, the latest ones go last
+    scope: Vec <(&'code str, String, String)>,
+
+    /// The counter making C names of variables unique
+    counter: usize,
+
+    indent: usize
+}
+
+impl <'input, 'code> CBackend <'input, 'code> {
+    fn baked(&self) -> &'input [BakedTypeBase <'code>] {
+        match &self.input.type_bases {
+            TypeBaseContainer::Baked(baked) => baked,
+            _ => unreachable!()
+        }
+    }
+
+    fn builtin(&self, idx: usize) -> (&'static str, &'static str, &'static str) {
+        let name = BUILTIN_BAKED_TYPES[idx].base.name.data;
+        *C_BUILTIN_TYPES.iter().find(|(oko, _, _)| *oko == name).expect("every builtin type has a C counterpart")
+    }
+
+    fn line(&self, out: &mut String, line: &str) {
+        out.push_str(&"\t".repeat(self.indent));
+        out.push_str(line);
+        out.push('\n')
+    }
+
+    fn fresh(&mut self, name: &str) -> String {
+        self.counter += 1;
+        format!("v{}_{name}", self.counter)
+    }
+
+    /// Returns the name of the `ty` or `enum` type with the index `idx` to be used in the names of its struct and printer
+    fn type_name(&self, idx: u32) -> String {
+        let base = &self.baked()[idx as usize];
+
+        // The types of the modules other than the root one are prefixed with the index of the module
+        let name = match base.module {
+            Some(module) if module != 0 => format!("m{module}_{}", base.name.data),
+            _ => String::from(base.name.data)
+        };
+
+        // The instances of a generic type share its name
+        match base.instance_of {
+            Some(_) => format!("{name}_{idx}"),
+            None => name
+        }
+    }
+
+    /// Returns the name of the C function for the function with the index `idx` in `ParseInput.top_level_items`
+    fn fn_name(&self, idx: usize) -> String {
+        let fun = match &self.input.top_level_items[idx] {
+            Item::Fn(fun) => fun,
+            _ => unreachable!()
+        };
+
+        // The functions of types are prefixed with the names of the types, which cannot clash
+        // with the names of other functions since the names have no underscores.
+        // The functions of the modules other than the root one are prefixed with the index of the module
+        let name = match fun.owner {
+            Some(owner) => format!("{}_{}", self.type_name(owner), fun.name.data),
+            None if fun.module != 0 => format!("m{}_{}", fun.module, fun.name.data),
+            None => String::from(fun.name.data)
+        };
+
+        // The instances of a generic function share its name
+        if fun.is_instance() {
+            format!("oko_fn_{name}_{idx}")
+        } else {
+            format!("oko_fn_{name}")
+        }
+    }
+
+    /// Returns the C type for `ty`, defining its struct first if needed
+    fn c_type(&mut self, ty: &TypeIndex) -> String {
+        match ty {
+            // Only the instances of generic functions are lowered, and they have no type parameters
+            TypeIndex::Generic(_) | TypeIndex::Applied(..) => unreachable!(),
+            TypeIndex::Tuple(types) if types.is_empty() => String::from("struct oko_unit"),
+            TypeIndex::Tuple(types) => {
+                let idx = match self.tuples.iter().position(|x| x == ty) {
+                    Some(idx) => idx,
+                    None => {
+                        let fields = types.iter().map(|ty| self.c_type(ty)).collect::<Vec <_>>();
+
+                        self.tuples.push(ty.clone());
+                        let idx = self.tuples.len() - 1;
+
+                        let _ = writeln!(self.structs, "struct oko_tuple_{idx} {{");
+                        for (i, field) in fields.iter().enumerate() {
+                            let _ = writeln!(self.structs, "\t{field} _{i};");
+                        }
+                        self.structs += "};\n\n";
+
+                        idx
+                    }
+                };
+
+                format!("struct oko_tuple_{idx}")
+            },
+            TypeIndex::Scalar(idx) => {
+                let base = &self.baked()[*idx as usize];
+
+                match &base.kind {
+                    BakedTypeBaseKind::Builtin(builtin) => String::from(self.builtin(*builtin).1),
+                    BakedTypeBaseKind::TypeProduct(fields) => {
+                        if !self.user_types.contains(idx) {
+                            self.user_types.push(*idx);
+
+                            let fields = fields.vec
+                                .iter()
+                                .map(|field| (field.name.data, self.c_type(&field.ty)))
+                                .collect::<Vec <_>>();
+
+                            let _ = writeln!(self.structs, "struct oko_ty_{} {{", self.type_name(*idx));
+                            for (name, ty) in &fields {
+                                let _ = writeln!(self.structs, "\t{ty} f_{name};");
+                            }
+                            if fields.is_empty() {
+                                self.structs += "\tchar _;\n";
+                            }
+                            self.structs += "};\n\n";
+                        }
+
+                        format!("struct oko_ty_{}", self.type_name(*idx))
+                    },
+                    BakedTypeBaseKind::TypeSum(variants) => {
+                        if !self.user_types.contains(idx) {
+                            self.user_types.push(*idx);
+
+                            let variants = variants.vec
+                                .iter()
+                                .map(|variant| (variant.name.data, variant.payload.iter().map(|ty| self.c_type(ty)).collect::<Vec <_>>()))
+                                .collect::<Vec <_>>();
+
+                            let _ = writeln!(self.structs, "struct oko_ty_{} {{\n\tunsigned tag;", self.type_name(*idx));
+                            if variants.iter().any(|(_, payload)| !payload.is_empty()) {
+                                self.structs += "\tunion {\n";
+                                for (name, payload) in variants.iter().filter(|(_, payload)| !payload.is_empty()) {
+                                    self.structs += "\t\tstruct {\n";
+                                    for (i, ty) in payload.iter().enumerate() {
+                                        let _ = writeln!(self.structs, "\t\t\t{ty} _{i};");
+                                    }
+                                    let _ = writeln!(self.structs, "\t\t}} v_{name};");
+                                }
+                                self.structs += "\t} u;\n";
+                            }
+                            self.structs += "};\n\n";
+                        }
+
+                        format!("struct oko_ty_{}", self.type_name(*idx))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the name of the function printing values of `ty` the way the interpreter does,
+    /// defining it first if needed
+    fn printer(&mut self, ty: &TypeIndex) -> String {
+        if let Some((_, name)) = self.printers.iter().find(|(x, _)| x == ty) {
+            return name.clone()
+        }
+
+        let c_type = self.c_type(ty);
+        let mut body = String::new();
+
+        let name = match ty {
+            TypeIndex::Generic(_) | TypeIndex::Applied(..) => unreachable!(),
+            TypeIndex::Tuple(types) if types.is_empty() => {
+                body += "\tfputs(\"()\", stdout);\n";
+                String::from("oko_print_unit")
+            },
+            TypeIndex::Tuple(types) => {
+                body += "\tputchar('(');\n";
+                for (i, ty) in types.iter().enumerate() {
+                    if i != 0 {
+                        body += "\tfputs(\", \", stdout);\n";
+                    }
+                    let printer = self.printer(ty);
+                    let _ = writeln!(body, "\t{printer}(v._{i});");
+                }
+                if types.len() == 1 {
+                    body += "\tputchar(',');\n";
+                }
+                body += "\tputchar(')');\n";
+                format!("oko_print_{}", &c_type["struct oko_".len()..])
+            },
+            TypeIndex::Scalar(idx) => {
+                let base = &self.baked()[*idx as usize];
+
+                match &base.kind {
+                    BakedTypeBaseKind::Builtin(builtin) => match self.builtin(*builtin) {
+                        (name @ ("i128" | "u128"), _, _) => {
+                            // There is no `printf` format for them, the digits are printed from the last one
+                            let is_signed = name == "i128";
+                            let _ = write!(body, "\
+\tunsigned __int128 u = {};
+\tchar digits[40];
+\tint len = 0;
+\tdo {{
+\t\tdigits[len++] = (char)('0' + (int)(u % 10));
+\t\tu /= 10;
+\t}} while (u != 0);
+{}\twhile (len != 0) putchar(digits[--len]);
+", if is_signed { "v < 0 ? -(unsigned __int128)v : (unsigned __int128)v" } else { "v" }, if is_signed { "\tif (v < 0) putchar('-');\n" } else { "" });
+                            format!("oko_print_{name}")
+                        },
+                        ("bool", _, _) => {
+                            body += "\tfputs(v ? \"true\" : \"false\", stdout);\n";
+                            String::from("oko_print_bool")
+                        },
+                        (name, _, format) => {
+                            let _ = writeln!(body, "\tprintf({format}, v);");
+                            format!("oko_print_{name}")
+                        }
+                    },
+                    BakedTypeBaseKind::TypeProduct(fields) => {
+                        let _ = writeln!(body, "\tfputs(\"{}\", stdout);", base.name.data);
+                        for (i, field) in fields.vec.iter().enumerate() {
+                            let printer = self.printer(&field.ty);
+                            let separator = if i == 0 { " { " } else { ", " };
+                            let _ = writeln!(body, "\tfputs(\"{separator}{}: \", stdout);\n\t{printer}(v.f_{});", field.name.data, field.name.data);
+                        }
+                        if !fields.vec.is_empty() {
+                            body += "\tfputs(\" }\", stdout);\n";
+                        }
+                        format!("oko_print_ty_{}", self.type_name(*idx))
+                    },
+                    BakedTypeBaseKind::TypeSum(variants) => {
+                        body += "\tswitch (v.tag) {\n";
+                        for (tag, variant) in variants.vec.iter().enumerate() {
+                            let _ = writeln!(body, "\tcase {tag}:\n\t\tfputs(\"{}{}\", stdout);", variant.name.data, if variant.payload.is_empty() { "" } else { "(" });
+                            for (i, ty) in variant.payload.iter().enumerate() {
+                                if i != 0 {
+                                    body += "\t\tfputs(\", \", stdout);\n";
+                                }
+                                let printer = self.printer(ty);
+                                let _ = writeln!(body, "\t\t{printer}(v.u.v_{}._{i});", variant.name.data);
+                            }
+                            if !variant.payload.is_empty() {
+                                body += "\t\tputchar(')');\n";
+                            }
+                            body += "\t\tbreak;\n";
+                        }
+                        body += "\t}\n";
+                        format!("oko_print_ty_{}", self.type_name(*idx))
+                    }
+                }
+            }
+        };
+
+        let _ = write!(self.helpers, "static void {name}({c_type} v) {{\n{body}}}\n\n");
+        self.printers.push((ty.clone(), name.clone()));
+
+        name
+    }
+
+    /// Returns the name of the function applying the checked arithmetic operation `op`
+    /// (`add`, `sub`, `mul`, `div` or `neg`) to values of the builtin integer type `name`,
+    /// defining it first if needed, the function stops the program the same way the interpreter does
+    fn arith_helper(&mut self, op: &'static str, name: &'static str, c_type: &str) -> String {
+        if !self.arith_helpers.contains(&(op, name)) {
+            self.arith_helpers.push((op, name));
+
+            let is_signed = name.starts_with('i');
+            let body = match op {
+                "add" | "sub" | "mul" => {
+                    let verb = match op {
+                        "add" => "add",
+                        "sub" => "subtract",
+                        _ => "multiply"
+                    };
+
+                    format!("\t{c_type} r;\n\tif (__builtin_{op}_overflow(a, b, &r)) oko_panic(\"attempt to {verb} with overflow\", file, line, column);\n\treturn r;\n")
+                },
+                "div" => format!(
+                    "\tif (b == 0) oko_panic(\"attempt to divide by zero\", file, line, column);\n{}\treturn a / b;\n",
+                    // The minimum divided by -1 is the only quotient out of the range
+                    if is_signed { format!("\t{c_type} r;\n\tif (b == -1 && __builtin_sub_overflow(({c_type}) 0, a, &r)) oko_panic(\"attempt to divide with overflow\", file, line, column);\n") } else { String::new() }
+                ),
+                "neg" => format!("\t{c_type} r;\n\tif (__builtin_sub_overflow(({c_type}) 0, a, &r)) oko_panic(\"attempt to negate with overflow\", file, line, column);\n\treturn r;\n"),
+                _ => unreachable!()
+            };
+
+            let args = if op == "neg" { format!("{c_type} a") } else { format!("{c_type} a, {c_type} b") };
+            let _ = write!(self.helpers, "static inline {c_type} oko_{op}_{name}({args}, unsigned file, int line, int column) {{\n{body}}}\n\n");
+        }
+
+        format!("oko_{op}_{name}")
+    }
+
+    /// Returns the name of the builtin integer type with the C type `ty` together with its C type,
+    /// floats and other types are left out as their arithmetic needs no checks
+    fn integer_type(ty: &str) -> Option <(&'static str, &'static str)> {
+        C_BUILTIN_TYPES
+            .iter()
+            .find(|(name, c_type, _)| *c_type == ty && !matches!(*name, "f32" | "f64" | "bool"))
+            .map(|(name, c_type, _)| (*name, *c_type))
+    }
+
+    fn signature(&mut self, idx: usize, fun: &Fn <'code>) -> String {
+        let args = fun.args
+            .iter()
+            .map(|arg| format!("{} a_{}", self.c_type(&arg.ty), arg.name.data))
+            .collect::<Vec <_>>();
+
+        let args = if args.is_empty() {
+            String::from("void")
+        } else {
+            args.join(", ")
+        };
+
+        format!("{} {}({args})", self.c_type(&fun.ret_ty), self.fn_name(idx))
+    }
+
+    fn function(&mut self, fun: &Fn <'code>, signature: &str) -> String {
+        let body = match &self.input.fn_body_bases {
+            FnBodyContainer::Baked(baked) => &baked[fun.body.base_index as usize],
+            _ => unreachable!()
+        };
+
+        self.scope = fun.args
+            .iter()
+            .map(|arg| (arg.name.data, format!("a_{}", arg.name.data), self.c_type(&arg.ty)))
+            .collect();
+
+        self.module = fun.module;
+
+        let mut out = format!("static {signature} {{\n");
+
+        self.indent = 1;
+        let value = self.block(&body.body, &mut out);
+        self.line(&mut out, &format!("return {value};"));
+        self.indent = 0;
+
+        out += "}\n\n";
+        out
+    }
+
+    /// Writes the statements into `out`, returning the C expression of the value of the block
+    fn block(&mut self, stmts: &[BakedFnBodyStmt <'code>], out: &mut String) -> String {
+        let len = self.scope.len();
+
+        let mut value = String::from("OKO_UNIT");
+
+        for (i, stmt) in stmts.iter().enumerate() {
+            value = match stmt {
+                BakedFnBodyStmt::Expr(expr) => {
+                    let (expr, _) = self.expr(expr, out);
+
+                    if i + 1 != stmts.len() {
+                        self.line(out, &format!("(void){expr};"));
+                    }
+
+                    expr
+                },
+                BakedFnBodyStmt::Binding(binding) => {
+                    let (expr, _) = self.expr(&binding.value, out);
+                    self.bind(&binding.pattern, expr, &binding.value.ty, out);
+                    String::from("OKO_UNIT")
+                }
+            }
+        }
+
+        self.scope.truncate(len);
+
+        value
+    }
+
+    fn bind(&mut self, pattern: &BindingPattern <'code>, expr: String, ty: &TypeIndex, out: &mut String) {
+        let c_type = self.c_type(ty);
+
+        match (pattern, ty) {
+            (BindingPattern::Name(name), _) => {
+                let variable = self.fresh(name.data);
+                self.line(out, &format!("{c_type} {variable} = {expr};"));
+                self.scope.push((name.data, variable, c_type))
+            },
+            (BindingPattern::Tuple(patterns, _), TypeIndex::Tuple(types)) => {
+                let tuple = self.fresh("tuple");
+                self.line(out, &format!("{c_type} {tuple} = {expr};"));
+                for (i, (pattern, ty)) in patterns.iter().zip(types).enumerate() {
+                    self.bind(pattern, format!("{tuple}._{i}"), ty, out)
+                }
+            },
+            _ => unreachable!()
+        }
+    }
+
+    /// Writes the statements the expression needs into `out`,
+    /// returning the C expression and its C type
+    fn expr(&mut self, expr: &dyn AsExprNode <'code>, out: &mut String) -> (String, String) {
+        match expr.node() {
+            ExprNode::Unary { op, value, span } => {
+                let (value, ty) = self.expr(value, out);
+
+                match op {
+                    UnaryOperator::Pos => (format!("(({ty})(+{value}))"), ty),
+                    UnaryOperator::Neg => match Self::integer_type(&ty) {
+                        Some((name, c_type)) => {
+                            let helper = self.arith_helper("neg", name, c_type);
+                            (format!("{helper}({value}, {}, {}, {})", self.module, span.start.line, span.start.column), ty)
+                        },
+                        None => (format!("(({ty})(-{value}))"), ty)
+                    },
+                    UnaryOperator::Not => (format!("(!{value})"), ty)
+                }
+            },
+            ExprNode::Binary { op: op @ (BinaryOperator::And | BinaryOperator::Or), left, right, .. } => {
+                let (left, _) = self.expr(left, out);
+
+                // The right operand is evaluated only if needed
+                let mut right_out = String::new();
+                self.indent += 1;
+                let (right, _) = self.expr(right, &mut right_out);
+                self.indent -= 1;
+
+                let operator = if op == BinaryOperator::And { "&&" } else { "||" };
+
+                if right_out.is_empty() {
+                    return (format!("({left} {operator} {right})"), String::from("bool"))
+                }
+
+                let result = self.fresh("logic");
+                self.line(out, &format!("bool {result} = {left};"));
+                self.line(out, &format!("if ({}{result}) {{", if op == BinaryOperator::And { "" } else { "!" }));
+                *out += &right_out;
+                self.line(out, &format!("\t{result} = {right};"));
+                self.line(out, "}");
+
+                (result, String::from("bool"))
+            },
+            ExprNode::Binary { op, left, right, span } => {
+                let (left, ty) = self.expr(left, out);
+                let (right, _) = self.expr(right, out);
+
+                let operator = match op {
+                    BinaryOperator::Mul => "*",
+                    BinaryOperator::Div => "/",
+                    BinaryOperator::Add => "+",
+                    BinaryOperator::Sub => "-",
+                    BinaryOperator::Eq => "==",
+                    BinaryOperator::Ne => "!=",
+                    BinaryOperator::Le => "<=",
+                    BinaryOperator::Ge => ">=",
+                    BinaryOperator::Lt => "<",
+                    BinaryOperator::Gt => ">",
+                    BinaryOperator::And | BinaryOperator::Or => unreachable!()
+                };
+
+                match op {
+                    BinaryOperator::Mul | BinaryOperator::Div | BinaryOperator::Add | BinaryOperator::Sub => match Self::integer_type(&ty) {
+                        Some((name, c_type)) => {
+                            let helper_op = match op {
+                                BinaryOperator::Mul => "mul",
+                                BinaryOperator::Div => "div",
+                                BinaryOperator::Add => "add",
+                                _ => "sub"
+                            };
+                            let helper = self.arith_helper(helper_op, name, c_type);
+                            (format!("{helper}({left}, {right}, {}, {}, {})", self.module, span.start.line, span.start.column), ty)
+                        },
+                        None => (format!("(({ty})({left} {operator} {right}))"), ty)
+                    },
+                    _ => (format!("({left} {operator} {right})"), String::from("bool"))
+                }
+            },
+            ExprNode::Call(call) => {
+                let fun = call.get_fun(self.input);
+
+                let args = call.args.iter().map(|arg| self.expr(arg, out).0).collect::<Vec <_>>();
+
+                (format!("{}({})", self.fn_name(call.fun), args.join(", ")), self.c_type(&fun.ret_ty))
+            },
+            ExprNode::Try(try_expr) => {
+                let (value, _) = self.expr(&try_expr.value, out);
+
+                let fork_type = match &self.input.top_level_items[try_expr.fun] {
+                    Item::Fn(fun) => self.c_type(&fun.ret_ty),
+                    _ => unreachable!()
+                };
+
+                let fork = self.fresh("fork");
+                self.line(out, &format!("{fork_type} {fork} = {}({value});", self.fn_name(try_expr.fun)));
+
+                // On `Break` its payload is returned at once
+                self.line(out, &format!("if ({fork}.tag == {}) return {fork}.u.v_Break._0;", TryExprFull::BREAK));
+
+                (format!("{fork}.u.v_Continue._0"), self.c_type(&try_expr.ty))
+            },
+            ExprNode::Primitive(primitive) => match primitive {
+                PrimitiveExpr::Ident(name) => {
+                    let (_, variable, ty) = self.scope
+                        .iter()
+                        .rev()
+                        .find(|(variable, _, _)| *variable == name.data)
+                        .expect("variables are checked to exist during the baking");
+
+                    (variable.clone(), ty.clone())
+                },
+                PrimitiveExpr::Int(int) => {
+                    let (_, ty, _) = C_BUILTIN_TYPES.iter().find(|(name, _, _)| *name == IntExpr::TYPE).unwrap();
+                    (format!("(({ty}){})", int.value), String::from(*ty))
+                },
+                PrimitiveExpr::Bool(bool) => (bool.data.to_string(), String::from("bool")),
+                PrimitiveExpr::If(if_expr) => {
+                    let ty = match &if_expr.otherwise {
+                        Some(_) => if_expr.branches[0].body.body.last().map(BakedFnBodyStmt::ty).unwrap_or(TypeIndex::UNIT_TUPLE),
+                        None => TypeIndex::UNIT_TUPLE
+                    };
+                    let c_type = self.c_type(&ty);
+
+                    let result = self.fresh("if");
+                    if ty.is_unit_tuple() {
+                        self.line(out, &format!("{c_type} {result} = OKO_UNIT;"));
+                    } else {
+                        self.line(out, &format!("{c_type} {result};"));
+                    }
+
+                    self.if_chain(&if_expr.branches, if_expr.otherwise.as_ref(), &result, out);
+
+                    (result, c_type)
+                },
+                PrimitiveExpr::Match(match_expr) => {
+                    let (value, value_c_type) = self.expr(&match_expr.value, out);
+
+                    let scrutinee = self.fresh("scrutinee");
+                    self.line(out, &format!("{value_c_type} {scrutinee} = {value};"));
+
+                    let ty = match_expr.arms[0].body.body.last().map(BakedFnBodyStmt::ty).unwrap_or(TypeIndex::UNIT_TUPLE);
+                    let c_type = self.c_type(&ty);
+
+                    let result = self.fresh("match");
+                    self.line(out, &format!("{c_type} {result};"));
+
+                    for (i, arm) in match_expr.arms.iter().enumerate() {
+                        let mut conditions = vec![];
+                        self.conditions(&arm.pattern, &scrutinee, &mut conditions);
+
+                        let opener = if conditions.is_empty() {
+                            String::from("{")
+                        } else {
+                            format!("if ({}) {{", conditions.join(" && "))
+                        };
+
+                        if i == 0 {
+                            self.line(out, &opener)
+                        } else {
+                            self.line(out, &format!("}} else {opener}"))
+                        }
+
+                        let len = self.scope.len();
+                        self.indent += 1;
+                        self.bind_pattern(&arm.pattern, scrutinee.clone(), &match_expr.value.ty, out);
+                        let value = self.block(&arm.body.body, out);
+                        self.line(out, &format!("{result} = {value};"));
+                        self.indent -= 1;
+                        self.scope.truncate(len);
+
+                        // The arms after the one matching everything are never reached
+                        if conditions.is_empty() {
+                            break
+                        }
+                    }
+
+                    self.line(out, "}");
+
+                    (result, c_type)
+                },
+                PrimitiveExpr::Braced(braced) => self.expr(&braced.value, out),
+                PrimitiveExpr::Tuple(tuple) => {
+                    if tuple.value.is_empty() {
+                        return (String::from("OKO_UNIT"), String::from("struct oko_unit"))
+                    }
+
+                    let values = tuple.value.iter().map(|expr| self.expr(expr, out).0).collect::<Vec <_>>();
+
+                    let ty = self.c_type(&TypeIndex::Tuple(tuple.value.iter().map(|expr| expr.ty.clone()).collect()));
+
+                    (format!("(({ty}){{{}}})", values.join(", ")), ty)
+                },
+                PrimitiveExpr::Variant(variant) => {
+                    let values = variant.args.iter().map(|arg| self.expr(arg, out).0).collect::<Vec <_>>();
+
+                    let ty = self.c_type(&TypeIndex::Scalar(variant.ty));
+                    let (_, definition) = variant.get_variant(self.input);
+
+                    if values.is_empty() {
+                        (format!("(({ty}){{.tag = {}}})", variant.variant), ty)
+                    } else {
+                        (format!("(({ty}){{.tag = {}, .u.v_{} = {{{}}}}})", variant.variant, definition.name.data, values.join(", ")), ty)
+                    }
+                },
+                PrimitiveExpr::Product(product) => {
+                    let values = product.args.iter().map(|arg| self.expr(arg, out).0).collect::<Vec <_>>();
+
+                    let ty = self.c_type(&TypeIndex::Scalar(product.ty));
+
+                    // The struct of a type with no fields has a placeholder field
+                    if values.is_empty() {
+                        (format!("(({ty}){{0}})"), ty)
+                    } else {
+                        (format!("(({ty}){{{}}})", values.join(", ")), ty)
+                    }
+                },
+                PrimitiveExpr::Field(field) => {
+                    let (value, _) = self.expr(&field.value, out);
+
+                    (format!("{value}.f_{}", field.name.data), self.c_type(&field.ty))
+                },
+                PrimitiveExpr::MethodCall(_) => unreachable!("method calls are seen as calls")
+            }
+        }
+    }
+
+    /// Pushes the C conditions checking whether the value at `path` matches `pattern` to `conditions`
+    fn conditions(&mut self, pattern: &Pattern <'code>, path: &str, conditions: &mut Vec <String>) {
+        match pattern {
+            Pattern::Wildcard(_) | Pattern::Binding(_) => (),
+            Pattern::Int(int) => {
+                let (_, ty, _) = C_BUILTIN_TYPES.iter().find(|(name, _, _)| *name == IntExpr::TYPE).unwrap();
+                conditions.push(format!("{path} == (({ty}){})", int.data))
+            },
+            Pattern::Bool(bool) => conditions.push(format!("{}{path}", if bool.data { "" } else { "!" })),
+            Pattern::Tuple(patterns, _) => for (i, pattern) in patterns.iter().enumerate() {
+                self.conditions(pattern, &format!("{path}._{i}"), conditions)
+            },
+            Pattern::Product { ty, fields, .. } => {
+                let names = match &self.baked()[*ty as usize].kind {
+                    BakedTypeBaseKind::TypeProduct(names) => names,
+                    _ => unreachable!()
+                };
+
+                for (field, name) in fields.iter().zip(&names.vec) {
+                    self.conditions(field, &format!("{path}.f_{}", name.name.data), conditions)
+                }
+            },
+            Pattern::Variant { ty, variant, args, .. } => {
+                let name = match &self.baked()[*ty as usize].kind {
+                    BakedTypeBaseKind::TypeSum(variants) => variants.vec[*variant].name.data,
+                    _ => unreachable!()
+                };
+
+                conditions.push(format!("{path}.tag == {variant}"));
+
+                for (i, arg) in args.iter().enumerate() {
+                    self.conditions(arg, &format!("{path}.u.v_{name}._{i}"), conditions)
+                }
+            }
+        }
+    }
+
+    /// Declares the variables the `pattern` binds from the value of the type `ty` at `path`
+    fn bind_pattern(&mut self, pattern: &Pattern <'code>, path: String, ty: &TypeIndex, out: &mut String) {
+        match (pattern, ty) {
+            (Pattern::Wildcard(_) | Pattern::Int(_) | Pattern::Bool(_), _) => (),
+            (Pattern::Binding(name), _) => {
+                let c_type = self.c_type(ty);
+                let variable = self.fresh(name.data);
+                self.line(out, &format!("{c_type} {variable} = {path};"));
+                self.scope.push((name.data, variable, c_type))
+            },
+            (Pattern::Tuple(patterns, _), TypeIndex::Tuple(types)) => for (i, (pattern, ty)) in patterns.iter().zip(types).enumerate() {
+                self.bind_pattern(pattern, format!("{path}._{i}"), ty, out)
+            },
+            (Pattern::Product { ty, fields, .. }, _) => {
+                let names = match &self.baked()[*ty as usize].kind {
+                    BakedTypeBaseKind::TypeProduct(names) => names,
+                    _ => unreachable!()
+                };
+
+                for (field, name) in fields.iter().zip(&names.vec) {
+                    self.bind_pattern(field, format!("{path}.f_{}", name.name.data), &name.ty, out)
+                }
+            },
+            (Pattern::Variant { ty, variant, args, .. }, _) => {
+                let definition = match &self.baked()[*ty as usize].kind {
+                    BakedTypeBaseKind::TypeSum(variants) => &variants.vec[*variant],
+                    _ => unreachable!()
+                };
+
+                for (i, (arg, ty)) in args.iter().zip(&definition.payload).enumerate() {
+                    self.bind_pattern(arg, format!("{path}.u.v_{}._{i}", definition.name.data), ty, out)
+                }
+            },
+            _ => unreachable!()
+        }
+    }
+
+    /// Writes the branches as nested C `if`s assigning their values to `result`
+    fn if_chain(&mut self, branches: &[IfBranch <'code>], otherwise: Option <&Block <'code>>, result: &str, out: &mut String) {
+        let (condition, _) = self.expr(&branches[0].condition, out);
+
+        self.line(out, &format!("if ({condition}) {{"));
+        self.indent += 1;
+        let value = self.block(&branches[0].body.body, out);
+        self.line(out, &format!("{result} = {value};"));
+        self.indent -= 1;
+
+        if branches.len() > 1 {
+            self.line(out, "} else {");
+            self.indent += 1;
+            self.if_chain(&branches[1..], otherwise, result, out);
+            self.indent -= 1;
+        } else if let Some(otherwise) = otherwise {
+            self.line(out, "} else {");
+            self.indent += 1;
+            let value = self.block(&otherwise.body, out);
+            self.line(out, &format!("{result} = {value};"));
+            self.indent -= 1;
+        }
+
+        self.line(out, "}");
+    }
+}
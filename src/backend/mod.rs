@@ -0,0 +1 @@
+crate::modules!(c);
@@ -1,8 +1,9 @@
 use crate::*;
-use std::process::{ExitCode, Termination};
-use core::fmt::Display;
+use std::process::ExitCode;
+use core::fmt::Write;
 use core::ops::{Try, FromResidual, ControlFlow};
 use core::convert::Infallible;
+use core::sync::atomic::{AtomicBool, Ordering};
 use owo_colors::*;
 
 #[repr(transparent)]
@@ -22,7 +23,7 @@ impl <T> FromResidual for Result <T> {
     fn from_residual(residual: Result <Infallible>) -> Self {
         Self(Err(match residual.0 {
             Err(err) => err,
-            Ok(_) => unsafe { core::hint::unreachable_unchecked() }
+            Ok(never) => match never {}
         }))
     }
 }
@@ -46,15 +47,6 @@ impl <T> Try for Result <T> {
     }
 }
 
-impl Termination for Result <()> {
-    fn report(self) -> ExitCode {
-        match self.0 {
-            Ok(()) => ().report(),
-            Err(err) => err.report()
-        }
-    }
-}
-
 ///
 /// The resulting error explaining in details why something went wrong
 ///
@@ -67,83 +59,261 @@ pub struct Error {
     pub message: String,
 
     /// The clarification message, printed near the problem place
-    pub clarifying: String,
-
-    /// The name of the file where error occurred
-    pub filename: String,
-
-     /// The code of the file where error occurred
-    pub code: String
+    pub clarifying: String
 }
 
 impl Error {
     pub const STUB: Error = Error {
         span: Span::DEFAULT,
         message: String::new(),
-        clarifying: String::new(),
-        filename: String::new(),
-        code: String::new()
+        clarifying: String::new()
     };
 }
 
-impl Termination for Error {
-    fn report(self) -> ExitCode {
-        let ladjust = " ".repeat(self.span.start.line.max(self.span.end.line).to_string().len() + 1);
+impl Error {
+    /// Prints the error into stderr with the lines from the `source_map`
+    pub fn report(self, source_map: &SourceMap) -> ExitCode {
+        Diagnostic::from(self).render(source_map);
 
-        print!("{}{} ", "error".bright_red().bold(), ":".bold());
-        print_with_style_and_green_if_asterisks(&self.message, |v| print!("{}", v.bold()));
-        println!("{}", ":".bold());
+        ExitCode::FAILURE
+    }
+}
 
-        println!("{}{} {}:{:?}", &ladjust[1..], "-->".blue().bold(), self.filename, self.span.start);
-        println!("{ladjust}{}", "|".blue().bold());
+///
+/// How serious a diagnostic is, only errors make the compilation fail
+///
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+    Help
+}
 
-        for (linenum, line) in self.span.get_spanned_lines(&self.code).iter().enumerate() {
-            let linenum = linenum as u32;
+impl Severity {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Note => "note",
+            Self::Help => "help"
+        }
+    }
 
-            let idx = linenum + self.span.start.line;
-            let idx_stringified = idx.to_string();
-            let full_line = self.code.split('\n').nth(idx as usize - 1).unwrap();
+    fn style(self) -> Style {
+        match self {
+            Self::Error => Style::new().bright_red().bold(),
+            Self::Warning => Style::new().bright_yellow().bold(),
+            Self::Note => Style::new().bright_green().bold(),
+            Self::Help => Style::new().bright_cyan().bold()
+        }
+    }
+}
 
-            let ladjust2 = " ".repeat(ladjust.len() - idx_stringified.len() - 1);
+///
+/// A secondary place in the file related to a diagnostic
+///
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub span: Span,
+    pub message: String
+}
 
-            let circumflex_ladjsust = if linenum == 0 {
-                " ".repeat(self.span.start.column as usize)
-            } else {
-                String::new()
-            } + &" ".repeat(full_line.matches('\t').count() * (SPACES_IN_TAB - 1) as usize);
+///
+/// Anything the compiler has to say about the code -- an error, a warning or just a note
+///
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
 
-            // let underscoring_len = if line == "<EOF>" {
-            //     1
-            // } else {
-            //     line.len()
-            // };
+    /// The span of the main place in the file
+    pub span: Span,
+
+    /// The general message, printed on the top
+    pub message: String,
+
+    /// The clarification message, printed near the main place
+    pub clarifying: String,
 
-            print!("{ladjust2}{idx} {stick} {line}\n{ladjust}{stick}{circumflex_ladjsust}{underscoring} ",
-                idx = idx_stringified.blue().bold(),
-                stick = "|".blue().bold(),
-                line = full_line.red(),
-                underscoring = "^".repeat(1.max(line.len())).bright_red().bold())
+    /// Other places related to the diagnostic, printed after the main one
+    pub labels: Vec <Label>,
+
+    /// Notes and helps printed at the bottom
+    pub notes: Vec <(Severity, String)>
+}
+
+impl From <Error> for Diagnostic {
+    fn from(err: Error) -> Self {
+        Self {
+            severity: Severity::Error,
+            span: err.span,
+            message: err.message,
+            clarifying: err.clarifying,
+            labels: vec![],
+            notes: vec![]
         }
+    }
+}
 
-        print_with_style_and_green_if_asterisks(&self.clarifying, |v| print!("{}", v.bright_red().bold()));
-        println!();
+impl Diagnostic {
+    pub fn new(severity: Severity, span: Span, message: String, clarifying: String) -> Self {
+        Self {
+            severity,
+            span,
+            message,
+            clarifying,
+            labels: vec![],
+            notes: vec![]
+        }
+    }
 
-        ExitCode::FAILURE
+    pub fn with_label(mut self, span: Span, message: String) -> Self {
+        self.labels.push(Label { span, message });
+        self
+    }
+
+    pub fn with_note(mut self, severity: Severity, message: String) -> Self {
+        self.notes.push((severity, message));
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Prints the diagnostic into stderr, see [`Diagnostic::render_to_string`]
+    pub fn render(&self, source_map: &SourceMap) {
+        eprint!("{}", self.render_to_string(source_map))
+    }
+
+    ///
+    /// Returns the diagnostic the way it is printed
+    ///
+    /// The lines are taken from the `source_map`, a diagnostic with no file, like the one about
+    /// a builtin type, is printed with no lines then
+    ///
+    pub fn render_to_string(&self, source_map: &SourceMap) -> String {
+        let mut out = String::new();
+
+        let max_line = core::iter::once(self.span)
+            .chain(self.labels.iter().map(|label| label.span))
+            .map(|span| span.start.line.max(span.end.line))
+            .max()
+            .unwrap_or(1);
+
+        let ladjust = " ".repeat(max_line.to_string().len() + 1);
+
+        let file = match source_map.get(self.span.file) {
+            Some(file) => file,
+            None => {
+                write_message_header(&mut out, self.severity, &self.message, "");
+                return out
+            }
+        };
+
+        write_message_header(&mut out, self.severity, &self.message, ":");
+
+        let _ = writeln!(out, "{}{} {}:{:?}", &ladjust[1..], "-->".style(styled(Style::new().blue().bold())), file.name, self.span.start);
+        let _ = writeln!(out, "{ladjust}{}", "|".style(styled(Style::new().blue().bold())));
+
+        write_snippet(&mut out, file, self.span, &self.clarifying, '^', self.severity.style(), &ladjust);
+
+        for label in &self.labels {
+            let label_file = match source_map.get(label.span.file) {
+                Some(label_file) => label_file,
+                None => continue
+            };
+
+            // A label in another file is preceded by the name of that file
+            if label.span.file != self.span.file {
+                let _ = writeln!(out, "{}{} {}:{:?}", &ladjust[1..], ":::".style(styled(Style::new().blue().bold())), label_file.name, label.span.start);
+            }
+
+            write_snippet(&mut out, label_file, label.span, &label.message, '-', Style::new().blue().bold(), &ladjust);
+        }
+
+        for (severity, note) in &self.notes {
+            let _ = write!(out, "{ladjust}{} {}{} ", "=".style(styled(Style::new().blue().bold())), severity.name().style(styled(Style::new().bold())), ":".style(styled(Style::new().bold())));
+            write_with_style_and_green_if_asterisks(&mut out, note, Style::new());
+            out.push('\n');
+        }
+
+        out
     }
 }
 
-type Printer = fn(&dyn Display);
+/// Writes the lines of `span` in the `file` underscored with `marker`s and followed by `message` into `out`
+fn write_snippet(out: &mut String, file: &SourceMapFile, span: Span, message: &str, marker: char, style: Style, ladjust: &str) {
+    for (linenum, line) in span.get_spanned_lines(file).iter().enumerate() {
+        let linenum = linenum as u32;
+
+        let idx = linenum + span.start.line;
+        let idx_stringified = idx.to_string();
+        let full_line = file.line(idx);
+
+        let ladjust2 = " ".repeat(ladjust.len() - idx_stringified.len() - 1);
+
+        let circumflex_ladjsust = if linenum == 0 {
+            " ".repeat(span.start.column as usize)
+        } else {
+            String::new()
+        } + &" ".repeat(full_line.matches('\t').count() * (SPACES_IN_TAB - 1) as usize);
+
+        let _ = write!(out, "{ladjust2}{idx} {stick} {line}\n{ladjust}{stick}{circumflex_ladjsust}{underscoring} ",
+            idx = idx_stringified.style(styled(Style::new().blue().bold())),
+            stick = "|".style(styled(Style::new().blue().bold())),
+            line = full_line.style(styled(Style::new().red())),
+            underscoring = marker.to_string().repeat(1.max(line.len())).style(styled(style)));
+    }
+
+    write_with_style_and_green_if_asterisks(out, message, style);
+    out.push('\n');
+}
+
+///
+/// Reports an error that has no place in the code, like a file failed to be read
+///
+pub fn report_error_message(message: &str) -> ExitCode {
+    let mut out = String::new();
+    write_message_header(&mut out, Severity::Error, message, "");
+    eprint!("{out}");
+
+    ExitCode::FAILURE
+}
+
+static COLORED: AtomicBool = AtomicBool::new(true);
+
+/// Makes diagnostics be printed with or without colors
+pub fn set_colored(colored: bool) {
+    COLORED.store(colored, Ordering::Relaxed)
+}
+
+/// Returns `style` if diagnostics are printed with colors and the plain style otherwise
+fn styled(style: Style) -> Style {
+    if COLORED.load(Ordering::Relaxed) {
+        style
+    } else {
+        Style::new()
+    }
+}
+
+fn write_message_header(out: &mut String, severity: Severity, message: &str, end: &str) {
+    let _ = write!(out, "{}{} ", severity.name().style(styled(severity.style())), ":".style(styled(Style::new().bold())));
+    write_with_style_and_green_if_asterisks(out, message, Style::new().bold());
+    let _ = writeln!(out, "{}", end.style(styled(Style::new().bold())));
+}
 
-fn print_with_style_and_green_if_asterisks(message: &str, default: Printer) {
+/// Writes the `message` with the `style`, the parts in backticks are green
+fn write_with_style_and_green_if_asterisks(out: &mut String, message: &str, style: Style) {
     if let Some(start) = message.find('`') {
         let extra = start + '`'.len_utf8();
 
         let end = message[extra..].find('`').unwrap_or(message.len() - extra) + extra;
 
-        default(&&message[..start]);
-        print!("{}", (&message[start..=end]).green().bold());
-        print_with_style_and_green_if_asterisks(&message[end + message[end..].chars().next().map(char::len_utf8).unwrap_or(0)..], default)
+        let _ = write!(out, "{}", (&message[..start]).style(styled(style)));
+        let _ = write!(out, "{}", (&message[start..=end]).style(styled(Style::new().green().bold())));
+        write_with_style_and_green_if_asterisks(out, &message[end + message[end..].chars().next().map(char::len_utf8).unwrap_or(0)..], style)
     } else {
-        default(&message)
+        let _ = write!(out, "{}", (&message).style(styled(style)));
     }
 }
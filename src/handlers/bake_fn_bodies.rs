@@ -1,91 +1,189 @@
 use crate::*;
 
-pub fn bake_fn_bodies(input: &mut ParseInput) -> Result <()> {
+/// The types longer than this are cut in the notes telling which instance of a generic function has errors
+const MAX_TYPE_LENGTH_IN_NOTE: usize = 60;
+
+///
+/// Parses and type checks the bodies of all the functions
+///
+/// The bodies of generic functions are checked with their type parameters standing for any types.
+/// After that, every instance of a generic function requested by the calls in the bodies
+/// gets its own body baked from the same code with the concrete types, and is appended to the items
+///
+/// Errors in a body are reported and the rest of the bodies are baked anyway.
+/// A body with errors is replaced by an empty one, so the indices of the bodies after it stay right
+///
+pub fn bake_fn_bodies(input: &mut ParseInput) {
     let raw = match &mut input.fn_body_bases {
-        FnBodyContainer::Raw(raw) => core::mem::replace(raw, vec![]),
+        FnBodyContainer::Raw(raw) => core::mem::take(raw),
         _ => unreachable!()
     };
 
     let mut newly_baked_bodies = vec![];
 
-    let mut exprs = vec![];
-
     let old_token_stream = core::mem::replace(&mut input.stream, TokenStream::empty());
 
-    let items = core::mem::replace(&mut input.top_level_items, vec![]);
+    let mut items = core::mem::take(&mut input.top_level_items);
+
+    input.fn_instances.first_item = items.len();
 
-    for (idx, body) in raw.into_iter().enumerate() {
-        let tokens = body.body();
+    // The indices of the bodies with errors, the instances of generic functions with such bodies are not baked
+    let mut failed = vec![];
 
+    for (idx, code) in raw.iter().enumerate() {
         let fun = match items.iter().find(|item| matches!(item, Item::Fn(fun) if fun.body.base_index == idx as u32)).unwrap() {
             Item::Fn(fun) => fun,
             _ => unreachable!()
         };
 
-        let ctx = fun.get_context(&items);
+        let body = bake_body(input, fun, &items, code).unwrap_or_else(|| {
+            failed.push(idx as u32);
+            BakedFnBodyBase::EMPTY
+        });
+
+        newly_baked_bodies.push(body)
+    }
+
+    // Baking the instances may request more of them
+    let mut instance = 0;
 
-        input.stream = TokenStream::from(tokens);
+    while let Some((generic, type_args)) = input.fn_instances.list.get(instance).cloned() {
+        instance += 1;
 
-        while !input.is_exhausted() {
-            remove_newlines_and_tabs(input);
+        let generic = match &items[generic] {
+            Item::Fn(fun) => fun.clone(),
+            _ => unreachable!()
+        };
 
-            if input.is_exhausted() {
-                break
+        let fun = Fn {
+            name: generic.name,
+            doc: generic.doc.clone(),
+            owner: generic.owner,
+            module: generic.module,
+            visibility: generic.visibility,
+            is_generated: generic.is_generated,
+            is_method: generic.is_method,
+            args: generic.args.iter().map(|arg| TypedVariable {
+                name: arg.name,
+                doc: arg.doc.clone(),
+                ty: arg.ty.substitute(input, &generic.type_params, &type_args)
+            }).collect(),
+            ret_ty: generic.ret_ty.substitute(input, &generic.type_params, &type_args),
+            type_params: generic.type_params.clone(),
+            type_args,
+            body: FnBodyIndex {
+                base_index: (raw.len() + instance - 1) as u32
             }
+        };
 
-            let expr = Expr::parse(input, &ctx)?;
+        items.push(Item::Fn(fun));
 
-            exprs.push(expr)
+        // The instance keeps its place among the items and the bodies, since the calls refer to it by index
+        if failed.contains(&generic.body.base_index) {
+            newly_baked_bodies.push(BakedFnBodyBase::EMPTY);
+            continue
         }
 
-        // Check body non-emptiness
-        let last = match exprs.last() {
-            Some(x) => x,
-            None => return Result(Err(Error {
-                span: fun.name.span,
-                message: String::from("functions cannot have empty body"),
-                clarifying: String::from("help: try using `pass`"),
-                filename: input.filename.to_string(),
-                code: input.code.to_string()
-            }))
+        let fun = match items.last() {
+            Some(Item::Fn(fun)) => fun,
+            _ => unreachable!()
         };
 
-        // Check last expr's type and return types are same
-        if last.ty != fun.ret_ty {
-            return Result(Err(Error {
-                span: last.span(),
-                message: String::from("return type mismatch"),
-                clarifying: format!("expected `{:?}`, got `{:?}`", fun.ret_ty.debug(input), last.ty.debug(input)),
-                filename: input.filename.to_string(),
-                code: input.code.to_string()
-            }))
-        }
+        let len = input.diagnostics.len();
 
-        // Check all exprs(except the last one) have `()` type
-        let mut exprs_iter = exprs.iter();
-        exprs_iter.advance_back_by(1).unwrap();
-        for expr in exprs_iter {
-            if !expr.ty.is_unit_tuple() {
-                return Result(Err(Error {
-                    span: expr.span(),
-                    message: format!("type mismatch: non-return expression should have `()` type, got `{:?}`", expr.ty.debug(input)),
-                    clarifying: String::from("help: try using `drop` function"),
-                    filename: input.filename.to_string(),
-                    code: input.code.to_string()
-                }))
-            }
+        let body = bake_body(input, fun, &items, &raw[generic.body.base_index as usize]);
+
+        // The errors only some of the instances have are shown together with the types they are for
+        let note = format!("while instantiating `{}` with {}", fun.name.data, generic.type_params
+            .iter()
+            .zip(&fun.type_args)
+            .map(|(param, arg)| {
+                let mut arg = format!("{:?}", arg.debug(input));
+
+                if arg.chars().count() > MAX_TYPE_LENGTH_IN_NOTE {
+                    arg = arg.chars().take(MAX_TYPE_LENGTH_IN_NOTE).chain("...".chars()).collect()
+                }
+
+                format!("`{} = {arg}`", input.type_params[*param as usize].data)
+            })
+            .collect::<Vec <_>>()
+            .join(", "));
+
+        for diagnostic in input.diagnostics.drain(len..).collect::<Vec <_>>() {
+            input.report(diagnostic.with_note(Severity::Note, note.clone()))
         }
 
-        newly_baked_bodies.push(BakedFnBodyBase {
-            body: core::mem::replace(&mut exprs, vec![])
-        })
+        newly_baked_bodies.push(body.unwrap_or(BakedFnBodyBase::EMPTY))
     }
 
+    input.type_params_in_scope.clear();
+
     input.stream = old_token_stream;
 
     input.fn_body_bases = FnBodyContainer::Baked(newly_baked_bodies);
 
     input.top_level_items = items;
+}
+
+///
+/// Bakes the body of the function `fun` from the code `raw`, reporting the errors in it
+///
+/// Returns `None` if there are errors
+///
+fn bake_body <'code> (
+    input: &mut ParseInput <'code>,
+    fun: &Fn <'code>,
+    items: &[Item <'code>],
+    raw: &RawFnBodyBase <'code>
+) -> Option <BakedFnBodyBase <'code>> {
+    let code = match raw {
+        RawFnBodyBase::Code(_) => raw.body().unwrap(),
+        RawFnBodyBase::New(ty) => return Some(ProductExpr::new_body(fun, *ty))
+    };
+
+    let ctx = fun.get_context(items);
+
+    input.enter_module(fun.module);
+    input.stream = TokenStream::from(code);
+    input.type_params_in_scope = fun.type_params.clone();
+
+    let (Block { body }, ty) = match Block::parse_stream(input, &ctx).0 {
+        Ok(ok) => ok,
+        Err(err) => {
+            input.report(err);
+            return None
+        }
+    };
+
+    // Check body non-emptiness
+    let last = match body.last() {
+        Some(x) => x,
+        None => {
+            let diagnostic = Diagnostic::new(
+                Severity::Error,
+                fun.name.span,
+                String::from("functions cannot have empty body"),
+                String::from("the body of this function is empty")
+            ).with_note(Severity::Help, String::from("try using `pass`"));
+
+            input.report(diagnostic);
+            return None
+        }
+    };
+
+    // Check last expr's type and return types are same
+    if ty != fun.ret_ty {
+        let diagnostic = Diagnostic::new(
+            Severity::Error,
+            last.span(),
+            String::from("return type mismatch"),
+            format!("expected `{:?}`, got `{:?}`", fun.ret_ty.debug(input), ty.debug(input))
+        ).with_label(fun.name.span, format!("`{:?}` is the return type of this function", fun.ret_ty.debug(input)));
+
+        input.report(diagnostic)
+    }
 
-    Result(Ok(()))
+    Some(BakedFnBodyBase {
+        body
+    })
 }
@@ -1,34 +1,401 @@
 use crate::*;
 
-pub fn bake_raw_types(input: &mut ParseInput) -> Result <()> {
+///
+/// Replaces the raw type bases with the baked ones
+///
+/// Every type used without a definition or with a wrong number of type arguments is reported.
+/// The generic types applied to concrete arguments are replaced by their instances
+///
+/// Every `ty` type gets the function `new` taking all its fields, unless it has `@default new = none`.
+/// The `ty` types containing themselves through their fields are reported, since they have infinite size
+///
+pub fn bake_raw_types(input: &mut ParseInput) {
     let raw = match &mut input.type_bases {
-        TypeBaseContainer::Raw(raw) => core::mem::replace(raw, vec![]),
+        TypeBaseContainer::Raw(raw) => core::mem::take(raw),
         _ => unreachable!()
     };
 
     let mut newly_baked_types = vec![];
 
+    // The `ty` types that get `new` and its visibility
+    let mut news = vec![];
+
     for base in raw {
+        if let RawTypeBase::Backed(RawTypeDefinition { kind: RawTypeDefinitionKind::Product(_), new: Some(visibility), .. }) = &base {
+            news.push((newly_baked_types.len() as u32, *visibility))
+        }
+
         newly_baked_types.push(match base {
-            RawTypeBase::Backed(RawTypeDefinition { fields, name }) => BakedTypeBase {
-                kind: BakedTypeBaseKind::TypeProduct(fields),
-                name
+            RawTypeBase::Backed(RawTypeDefinition { kind, name, doc, module, params, .. }) => BakedTypeBase {
+                kind: match kind {
+                    RawTypeDefinitionKind::Product(fields) => BakedTypeBaseKind::TypeProduct(fields),
+                    RawTypeDefinitionKind::Sum(variants) => BakedTypeBaseKind::TypeSum(variants)
+                },
+                name,
+                doc,
+                module: Some(module),
+                params,
+                instance_of: None
             },
-            RawTypeBase::Stub(name) => if let Some(builtin) = BUILTIN_BAKED_TYPES.into_iter().find(|ty| ty.base.name == name) {
+            RawTypeBase::Stub { name, .. } => if let Some(builtin) = BUILTIN_BAKED_TYPES.into_iter().find(|ty| ty.base.name == name) {
                 builtin.base
             } else {
-                return Result(Err(Error {
+                input.report(Error {
                     span: name.span,
                     message: format!("the type `{}` has no definition", name.data),
-                    clarifying: String::from("a ghostly type"),
-                    filename: input.filename.to_string(),
-                    code: input.code.to_string()
-                }))
+                    clarifying: String::from("a ghostly type")
+                });
+                continue
             }
         })
     }
 
     input.type_bases = TypeBaseContainer::Baked(newly_baked_types);
 
-    Result(Ok(()))
+    // The types after the missing ones are shifted, so the indices pointing to them are wrong
+    if input.has_errors() {
+        return
+    }
+
+    check_type_args(input);
+
+    if input.has_errors() {
+        return
+    }
+
+    concretize(input);
+
+    check_recursive_types(input);
+
+    for (ty, visibility) in news {
+        generate_new(input, ty, visibility)
+    }
+}
+
+/// Adds the function `new` of the `ty` type with the index `ty`, which takes all the fields and creates the value
+fn generate_new(input: &mut ParseInput, ty: u32, visibility: Visibility) {
+    let base = match &input.type_bases {
+        TypeBaseContainer::Baked(baked) => &baked[ty as usize],
+        _ => unreachable!()
+    };
+
+    let args = match &base.kind {
+        BakedTypeBaseKind::TypeProduct(fields) => fields.vec.clone(),
+        _ => unreachable!()
+    };
+
+    // Only the types written in the code get `new`
+    let module = base.module.unwrap();
+    let name = base.name;
+    let params = base.params.clone();
+
+    let written = input.top_level_items.iter().find_map(|item| match item {
+        Item::Fn(fun) if fun.owner == Some(ty) && fun.name.data == "new" => Some(fun.name.span),
+        _ => None
+    });
+
+    if let Some(span) = written {
+        let err = Error {
+            span,
+            message: format!("the type `{}` already has the generated function `new`", name.data),
+            clarifying: String::from("help: add `@default new = none` before the type to write `new` by hand")
+        };
+
+        return input.report(err)
+    }
+
+    let ret_ty = match params.is_empty() {
+        true => TypeIndex::Scalar(ty),
+        false => TypeIndex::Applied(ty, params.iter().map(|param| TypeIndex::Generic(*param)).collect())
+    };
+
+    let fun = Fn {
+        name: Spanned {
+            data: "new",
+            span: name.span
+        },
+        doc: Doc::EMPTY,
+        owner: Some(ty),
+        module,
+        visibility,
+        is_generated: true,
+        is_method: false,
+        type_params: params,
+        type_args: vec![],
+        args,
+        ret_ty,
+        body: FnBodyIndex {
+            base_index: input.add_raw_fn_body_base(RawFnBodyBase::New(ty))
+        }
+    };
+
+    input.top_level_items.push(Item::Fn(fun))
+}
+
+/// Returns the types written in the definition of the type, each with the span of the name it belongs to
+fn written_types <'a> (base: &'a BakedTypeBase) -> Vec <(Span, &'a TypeIndex)> {
+    match &base.kind {
+        BakedTypeBaseKind::Builtin(_) => vec![],
+        BakedTypeBaseKind::TypeProduct(fields) => fields.vec.iter().map(|field| (field.name.span, &field.ty)).collect(),
+        BakedTypeBaseKind::TypeSum(variants) => variants.vec
+            .iter()
+            .flat_map(|variant| variant.payload.iter().map(|ty| (variant.name.span, ty)))
+            .collect()
+    }
+}
+
+///
+/// Checks the number of type arguments of the types in the definitions and in the signatures of functions
+///
+/// A generic type containing itself is also checked to have the same type arguments as its parameters,
+/// otherwise every its instance would need one more instance for other types
+///
+fn check_type_args(input: &mut ParseInput) {
+    let baked = match &input.type_bases {
+        TypeBaseContainer::Baked(baked) => baked,
+        _ => unreachable!()
+    };
+
+    let mut errors = vec![];
+
+    for (idx, base) in baked.iter().enumerate() {
+        let params = base.params.iter().map(|param| TypeIndex::Generic(*param)).collect::<Vec <_>>();
+
+        for (span, ty) in written_types(base) {
+            if let Err(err) = ty.check_type_args(input, span).0 {
+                errors.push(err);
+                continue
+            }
+
+            if !params.is_empty() && contains_with_other_args(ty, idx as u32, &params) {
+                errors.push(Error {
+                    span,
+                    message: format!("the generic type `{}` contains itself with other type arguments", base.name.data),
+                    clarifying: format!("help: use the parameters as they are, like `{:?}`", TypeIndex::Applied(idx as u32, params.clone()).debug(input))
+                })
+            }
+        }
+    }
+
+    for item in &input.top_level_items {
+        if let Item::Fn(fun) = item {
+            for (span, ty) in fun.args.iter().map(|arg| (arg.name.span, &arg.ty)).chain(core::iter::once((fun.name.span, &fun.ret_ty))) {
+                if let Err(err) = ty.check_type_args(input, span).0 {
+                    errors.push(err)
+                }
+            }
+        }
+    }
+
+    for err in errors {
+        input.report(err)
+    }
+}
+
+/// Returns whether the type `ty` has the type `base` applied to something other than `args` in it
+fn contains_with_other_args(ty: &TypeIndex, base: u32, args: &[TypeIndex]) -> bool {
+    match ty {
+        TypeIndex::Scalar(_) | TypeIndex::Generic(_) => false,
+        TypeIndex::Tuple(types) => types.iter().any(|ty| contains_with_other_args(ty, base, args)),
+        TypeIndex::Applied(idx, types) => (*idx == base && types != args) || types.iter().any(|ty| contains_with_other_args(ty, base, args))
+    }
+}
+
+/// Replaces the generic types applied to concrete arguments in the definitions and in the signatures with their instances
+fn concretize(input: &mut ParseInput) {
+    let defined = match &input.type_bases {
+        TypeBaseContainer::Baked(baked) => baked.len(),
+        _ => unreachable!()
+    };
+
+    for idx in 0..defined {
+        let kind = match &input.type_bases {
+            TypeBaseContainer::Baked(baked) => baked[idx].kind.clone(),
+            _ => unreachable!()
+        };
+
+        let kind = kind.substitute(input, &[], &[]);
+
+        match &mut input.type_bases {
+            TypeBaseContainer::Baked(baked) => baked[idx].kind = kind,
+            _ => unreachable!()
+        }
+    }
+
+    let mut items = core::mem::take(&mut input.top_level_items);
+
+    for item in &mut items {
+        if let Item::Fn(fun) = item {
+            for arg in &mut fun.args {
+                arg.ty = arg.ty.concretize(input)
+            }
+
+            fun.ret_ty = fun.ret_ty.concretize(input)
+        }
+    }
+
+    input.top_level_items = items;
+}
+
+/// The state of a type while the types are searched for cycles
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Visit {
+    New,
+    OnPath,
+    Done
+}
+
+/// A field of a `ty` type or a variant of an enum, through which the type holds the values of other types in place
+#[derive(Copy, Clone)]
+struct Member <'code> {
+    name: Spanned <&'code str>,
+    is_variant: bool
+}
+
+impl <'code> Member <'code> {
+    /// Returns how the member is named in the messages -- ``field `x` `` or ``variant `Some` ``
+    fn describe(&self) -> String {
+        format!("{} `{}`", if self.is_variant { "variant" } else { "field" }, self.name.data)
+    }
+}
+
+/// A member of the type with the index `.0`
+type MemberOf <'code> = (u32, Member <'code>);
+
+///
+/// Reports every cycle of the types containing each other through the fields of the `ty` types
+/// or the payloads of the variants of the enums, which are held in place too,
+/// with the chain of the fields and the variants it goes through
+///
+/// The members are followed through the tuples, but not through the type arguments.
+/// The instances of a generic type repeat its cycles, so a chain of the same members is reported once
+///
+fn check_recursive_types(input: &mut ParseInput) {
+    let baked = match &input.type_bases {
+        TypeBaseContainer::Baked(baked) => baked,
+        _ => unreachable!()
+    };
+
+    // The types every type contains through its members, each with the member
+    let edges = baked.iter().map(|base| {
+        let members = match &base.kind {
+            BakedTypeBaseKind::TypeProduct(fields) => fields.vec.iter().map(|field| (Member {
+                name: field.name,
+                is_variant: false
+            }, core::slice::from_ref(&field.ty))).collect(),
+            BakedTypeBaseKind::TypeSum(variants) => variants.vec.iter().map(|variant| (Member {
+                name: variant.name,
+                is_variant: true
+            }, variant.payload.as_slice())).collect(),
+            BakedTypeBaseKind::Builtin(_) => vec![]
+        };
+
+        members.into_iter().flat_map(|(member, types)| {
+            let mut contained = vec![];
+            for ty in types {
+                contained_types(ty, &mut contained)
+            }
+            contained.sort();
+            contained.dedup();
+            contained.into_iter().map(move |ty| (member, ty))
+        }).collect()
+    }).collect::<Vec <Vec <_>>>();
+
+    let mut visits = vec![Visit::New; baked.len()];
+    let mut cycles = vec![];
+
+    for ty in 0..baked.len() as u32 {
+        if visits[ty as usize] == Visit::New {
+            find_cycles(ty, &edges, &mut visits, &mut vec![], &mut cycles)
+        }
+    }
+
+    let mut reported = vec![];
+    let mut diagnostics = vec![];
+
+    for mut cycle in cycles {
+        let position = |(_, member): &MemberOf| (member.name.span.file, member.name.span.start.offset);
+
+        let mut key = cycle.iter().map(position).collect::<Vec <_>>();
+        key.sort();
+
+        if reported.contains(&key) {
+            continue
+        }
+
+        // The cycle is reported from the member written first, preferring the types written in the code to the instances
+        let first = (0..cycle.len()).min_by_key(|idx| (baked[cycle[*idx].0 as usize].instance_of.is_some(), position(&cycle[*idx]))).unwrap();
+        cycle.rotate_left(first);
+
+        reported.push(key);
+
+        let name = |ty: u32| match &baked[ty as usize] {
+            base if base.instance_of.is_none() && !base.params.is_empty() => TypeIndex::Applied(ty, base.params.iter().map(|param| TypeIndex::Generic(*param)).collect()),
+            _ => TypeIndex::Scalar(ty)
+        };
+
+        // The type each member contains, the last member contains the first type
+        let contains = |idx: usize| name(cycle[(idx + 1) % cycle.len()].0);
+
+        let (owner, first) = cycle[0];
+
+        let mut diagnostic = Diagnostic::new(
+            Severity::Error,
+            first.name.span,
+            format!("the type `{:?}` contains itself, so it has infinite size", name(owner).debug(input)),
+            format!("the {} contains `{:?}`", first.describe(), contains(0).debug(input))
+        );
+
+        for (idx, (owner, member)) in cycle.iter().enumerate().skip(1) {
+            diagnostic = diagnostic.with_label(
+                member.name.span,
+                format!("...whose {} of `{:?}` contains `{:?}`", member.describe(), name(*owner).debug(input), contains(idx).debug(input))
+            )
+        }
+
+        diagnostics.push(diagnostic.with_note(Severity::Help, String::from("remove a field or a payload of a variant of the cycle, there are no boxes or references to break it with yet")))
+    }
+
+    for diagnostic in diagnostics {
+        input.report(diagnostic)
+    }
+}
+
+/// Pushes the types the value of the type `ty` holds in place into `contained`, which are the ones in its tuples
+fn contained_types(ty: &TypeIndex, contained: &mut Vec <u32>) {
+    match ty {
+        TypeIndex::Scalar(idx) | TypeIndex::Applied(idx, _) => contained.push(*idx),
+        TypeIndex::Tuple(types) => for ty in types {
+            contained_types(ty, contained)
+        },
+        TypeIndex::Generic(_) => ()
+    }
+}
+
+/// Searches for the cycles through the type `ty` reached by the members in `path`, pushing them into `cycles`
+fn find_cycles <'code> (
+    ty: u32,
+    edges: &[Vec <(Member <'code>, u32)>],
+    visits: &mut [Visit],
+    path: &mut Vec <MemberOf <'code>>,
+    cycles: &mut Vec <Vec <MemberOf <'code>>>
+) {
+    visits[ty as usize] = Visit::OnPath;
+
+    for &(member, next) in &edges[ty as usize] {
+        path.push((ty, member));
+
+        match visits[next as usize] {
+            Visit::New => find_cycles(next, edges, visits, path, cycles),
+            Visit::OnPath => {
+                let start = path.iter().position(|(owner, _)| *owner == next).unwrap();
+                cycles.push(path[start..].to_vec())
+            },
+            Visit::Done => ()
+        }
+
+        path.pop();
+    }
+
+    visits[ty as usize] = Visit::Done
 }
@@ -0,0 +1,85 @@
+use crate::*;
+
+///
+/// Prepares the source code for tokenizing:
+/// removes the byte order mark and makes the line breaks be `\n`
+///
+pub fn prepare_code(code: &str) -> String {
+    code.strip_prefix('\u{feff}').unwrap_or(code).replace("\r\n", "\n")
+}
+
+///
+/// Runs the prepared `code` of the file named `filename` through every stage up to the baking
+/// and passes the baked program to `f`, like `compile_program` for a program of a single file
+///
+/// The file is added to the `source_map`, which renders the returned diagnostics
+///
+pub fn compile <R> (source_map: &mut SourceMap, filename: &str, code: &str, f: impl FnOnce(&ParseInput) -> R) -> Result <R, Vec <Diagnostic>> {
+    let file = SourceFile::new(source_map, vec![], filename.to_string(), code.to_string());
+    compile_program(source_map, &[file], f)
+}
+
+///
+/// Runs the prepared `files` of the program through every stage up to the baking
+/// and passes the baked program to `f`, since it borrows both the code and its tokens
+///
+/// The first of the `files` is the root one, the rest are the modules it uses,
+/// all of them are in the `source_map`
+///
+/// Stops after the first stage that reported errors, returning the diagnostics reported till then.
+/// So an error in the types, like an unknown field type, hides the errors in the function bodies,
+/// since the bodies are baked against the type indices which are wrong after such an error.
+/// Otherwise the warnings are left for `f` in `ParseInput.diagnostics`
+///
+/// The diagnostics are sorted by the place they are about
+///
+pub fn compile_program <R> (source_map: &SourceMap, files: &[SourceFile], f: impl FnOnce(&ParseInput) -> R) -> Result <R, Vec <Diagnostic>> {
+    let mut streams = vec![];
+    let mut errors = vec![];
+
+    for file in files {
+        match TokenStream::new(file.file, &file.code, file.indent).0 {
+            Ok(ok) => streams.push(ok),
+            Err(err) => errors.push(err.into())
+        }
+    }
+
+    if !errors.is_empty() {
+        return Result(Err(errors))
+    }
+
+    let modules = files.iter().zip(&streams).map(|(file, tokens)| Module {
+        path: &file.path,
+        filename: &file.filename,
+        file: file.file,
+        tokens,
+        types: vec![],
+        imports: vec![]
+    }).collect();
+
+    let mut input = ParseInput {
+        stream: TokenStream::from(&streams[0]),
+        modules,
+        source_map,
+        module: 0,
+        type_bases: TypeBaseContainer::new(),
+        fn_body_bases: FnBodyContainer::new(),
+        top_level_items: vec![],
+        type_params: vec![],
+        type_params_in_scope: vec![],
+        fn_instances: FnInstances::new(),
+        diagnostics: vec![]
+    };
+
+    for stage in [parse_code as fn(&mut ParseInput), bake_raw_types, bake_fn_bodies, warn_unused] {
+        stage(&mut input);
+
+        if input.has_errors() {
+            return Result(Err(input.take_diagnostics()))
+        }
+    }
+
+    input.diagnostics = input.take_diagnostics();
+
+    Result(Ok(f(&input)))
+}
@@ -0,0 +1,116 @@
+use crate::*;
+use std::path::{Path, PathBuf};
+
+///
+/// Reads the modules the `root` file at `root_path` uses, and the ones they use, till there are no new ones
+///
+/// The module `a::b` is the file `a/b.oko` in the directory of the root file. Every prefix of a path
+/// written in `use`, and the path together with every imported name, is a module if there is such a file,
+/// the paths with no files are left for `use` to report. Importing the root file is an error
+///
+/// Returns the root file followed by the modules, which are added to the `source_map`
+///
+pub fn load_modules(source_map: &mut SourceMap, root: SourceFile, root_path: &Path) -> Result <Vec <SourceFile>> {
+    let dir = root_path.parent().unwrap_or(Path::new(""));
+    let root_path = root_path.canonicalize().ok();
+
+    let mut files = vec![root];
+    let mut next = 0;
+
+    while next < files.len() {
+        let mut found = vec![];
+
+        // The file with errors in its tokens is reported while compiling
+        if let Ok(tokens) = TokenStream::new(files[next].file, &files[next].code, files[next].indent).0 {
+            for (path, span) in used_module_paths(&tokens) {
+                if files.iter().chain(&found).any(|file| file.path == path) {
+                    continue
+                }
+
+                let file = dir.join(path.iter().collect::<PathBuf>()).with_extension(SourceFile::EXTENSION);
+
+                if !file.is_file() {
+                    continue
+                }
+
+                // The root file is not a module of itself or of the modules it uses
+                if root_path.is_some() && file.canonicalize().ok() == root_path {
+                    return Result(Err(Error {
+                        span,
+                        message: String::from("the root file cannot be imported as a module"),
+                        clarifying: format!("`{}` is the root file", file.display())
+                    }))
+                }
+
+                let code = match std::fs::read_to_string(&file) {
+                    Ok(ok) => prepare_code(&ok),
+                    Err(err) => return Result(Err(Error {
+                        span,
+                        message: format!("failed to read the module `{}`", SourceFile::display_path(&path)),
+                        clarifying: format!("`{}`: {err}", file.display())
+                    }))
+                };
+
+                found.push(SourceFile {
+                    indent: files[0].indent,
+                    ..SourceFile::new(source_map, path, file.to_string_lossy().into_owned(), code)
+                })
+            }
+        }
+
+        files.extend(found);
+        next += 1
+    }
+
+    Result(Ok(files))
+}
+
+///
+/// Returns the paths in the `use` lines of the `tokens` that can be modules, each with the span of its last name
+///
+fn used_module_paths(tokens: &[Token]) -> Vec <(Vec <String>, Span)> {
+    let mut paths = vec![];
+
+    // `use` is only at the start of a line with no indentation,
+    // the indentation tokens are in the beginning of the lines
+    let lines = tokens.split(|token| token.kind == TokenKind::Newline);
+    let mut level = 0;
+
+    for line in lines {
+        let indentation = line.iter().take_while(|token| matches!(token.kind, TokenKind::Indent | TokenKind::Dedent)).collect::<Vec <_>>();
+
+        for token in &indentation {
+            if token.kind == TokenKind::Indent {
+                level += 1
+            } else {
+                level -= 1
+            }
+        }
+
+        let line = &line[indentation.len()..];
+
+        match line.first().map(|token| &token.kind) {
+            Some(TokenKind::Ident("use")) if level == 0 => (),
+            _ => continue
+        }
+
+        let mut path = vec![];
+
+        for (idx, token) in line.iter().enumerate().skip(1) {
+            let name = match token.kind {
+                TokenKind::Ident(name) => name.to_string(),
+                _ => continue
+            };
+
+            let mut candidate = path.clone();
+            candidate.push(name.clone());
+            paths.push((candidate, token.span));
+
+            if matches!(line.get(idx + 1).map(|token| &token.kind), Some(TokenKind::ColonColon)) {
+                path.push(name)
+            }
+        }
+    }
+
+    paths
+}
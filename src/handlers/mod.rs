@@ -1 +1 @@
-crate::modules!(bake_fn_bodies bake_raw_types parse_code remove_non_doc_comments);
+crate::modules!(bake_fn_bodies bake_raw_types parse_code load_modules compile warn_unused);
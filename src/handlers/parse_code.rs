@@ -1,6 +1,42 @@
 use crate::*;
 
-pub fn parse_code(input: &mut ParseInput) -> Result <()> {
+///
+/// Parses all the top-level items of all the modules
+///
+/// First the `use` lines and the names of the types of every module are collected,
+/// so that the types of other modules are known while the items are parsed
+///
+/// An item that fails to parse is reported and skipped till the next line with no indentation
+///
+pub fn parse_code(input: &mut ParseInput) {
+    for module in 0..input.modules.len() as u32 {
+        enter_module_code(input, module);
+        collect_declarations(input)
+    }
+
+    for module in 0..input.modules.len() as u32 {
+        enter_module_code(input, module);
+        parse_items(input)
+    }
+
+    check_imports(input)
+}
+
+/// Makes the module with the index `module` the current one and starts parsing its code
+fn enter_module_code(input: &mut ParseInput, module: u32) {
+    input.enter_module(module);
+    input.stream = TokenStream::from(input.modules[module as usize].tokens)
+}
+
+/// Returns whether the current line is a `use` line
+fn is_use(input: &mut ParseInput) -> bool {
+    check_if_the_next_token_is_keyword(input, &["use"])
+}
+
+/// Parses the `use` lines of the current module and collects the names of the types defined in it
+fn collect_declarations(input: &mut ParseInput) {
+    let module = input.module as usize;
+
     loop {
         remove_newlines(input);
 
@@ -8,10 +44,167 @@ pub fn parse_code(input: &mut ParseInput) -> Result <()> {
             break
         }
 
-        let item = Item::parse(input)?;
+        if is_use(input) {
+            match Import::parse_line(input).0 {
+                Ok(imports) => for import in imports {
+                    if let Some(previous) = input.modules[module].imports.iter().find(|previous| previous.name == import.name) {
+                        let err = Error {
+                            span: import.name.span,
+                            message: format!("duplicating imports: `{}` at {:?}...", previous.name.data, previous.name.span.start),
+                            clarifying: format!("...and now `{}` at {:?}", import.name.data, import.name.span.start)
+                        };
+
+                        input.report(err);
+                        continue
+                    }
+
+                    input.modules[module].imports.push(import)
+                },
+                Err(err) => input.report(err)
+            }
+        } else if check_if_the_next_token_is_keyword(input, &["ty", "enum"]) {
+            input.go_forward();
+
+            if let Ok(name) = input.ident_as_spanned_str().0 {
+                input.modules[module].types.push(name.data)
+            }
+        }
+
+        input.skip_to_next_line()
+    }
+}
+
+/// Parses the items of the current module, skipping the `use` lines
+fn parse_items(input: &mut ParseInput) {
+    loop {
+        // The doc comments are parsed together with the item they document,
+        // but are skipped here to see what the item is
+        let docs = input.get();
+
+        remove_newlines_and_docs(input);
+
+        if input.is_exhausted() {
+            break
+        }
+
+        if is_use(input) {
+            input.skip_to_next_line();
+            continue
+        }
+
+        let has_attributes = input.stream.buf[input.get()].kind == TokenKind::At;
+        let start = input.get();
+
+        input.set(docs);
+
+        match Item::parse(input).0 {
+            Ok(item) => input.top_level_items.push(item),
+            Err(err) => {
+                input.report(err);
+                input.set(start);
+                input.skip_to_next_line();
 
-        input.top_level_items.push(item)
+                // The attributes are on their own lines before the item, which is skipped too
+                if has_attributes {
+                    remove_newlines(input);
+
+                    while !input.is_exhausted() && input.stream.buf[input.get()].kind == TokenKind::At {
+                        input.skip_to_next_line();
+                        remove_newlines(input)
+                    }
+
+                    input.skip_to_next_line()
+                }
+            }
+        }
     }
+}
+
+///
+/// Checks that the imported items exist and do not clash with the items of the importing module
+///
+fn check_imports(input: &mut ParseInput) {
+    for module in 0..input.modules.len() {
+        input.enter_module(module as u32);
+
+        let mut errors = vec![];
 
-    Result(Ok(()))
+        for import in input.imports() {
+            let name = import.name.data;
+
+            let error = |span: Span, message: String, clarifying: String| Error {
+                span,
+                message,
+                clarifying
+            };
+
+            let path = |target: u32| SourceFile::display_path(input.modules[target as usize].path);
+
+            let is_fn = |target: u32, owner: Option <u32>| input.top_level_items.iter().any(|item| matches!(
+                item,
+                Item::Fn(fun) if fun.name.data == name && fun.module == target && fun.owner == owner
+            ));
+
+            match import.target {
+                ImportTarget::Module(_) => (),
+                ImportTarget::Item(target) if target == module as u32 => errors.push(error(
+                    import.name.span,
+                    format!("no module named `{name}`"),
+                    format!("there is no file `{name}.{}` next to the root file", SourceFile::EXTENSION)
+                )),
+                ImportTarget::Item(target) => if !input.modules[target as usize].types.contains(&name) && !is_fn(target, None) {
+                    errors.push(error(import.name.span, format!("no type or function named `{name}` in the module `{}`", path(target)), String::from("here")))
+                } else if input.modules[module].types.contains(&name) || is_fn(module as u32, None) {
+                    errors.push(error(import.name.span, format!("the imported `{name}` clashes with the item of the same name"), String::from("help: use it as `module::name` instead")))
+                },
+                ImportTarget::Member(target, ty) => {
+                    let raw = match &input.type_bases {
+                        TypeBaseContainer::Raw(raw) => raw,
+                        _ => unreachable!()
+                    };
+
+                    let (idx, def) = match raw.iter().enumerate().find_map(|(idx, base)| match base {
+                        RawTypeBase::Backed(def) if def.name == ty && def.module == target => Some((idx as u32, def)),
+                        _ => None
+                    }) {
+                        Some(x) => x,
+                        None if target == module as u32 => {
+                            errors.push(error(ty.span, format!("no module or type named `{}`", ty.data), String::from("here")));
+                            continue
+                        },
+                        None => {
+                            errors.push(error(ty.span, format!("no type named `{}` in the module `{}`", ty.data, path(target)), String::from("here")));
+                            continue
+                        }
+                    };
+
+                    let is_variant = matches!(&def.kind, RawTypeDefinitionKind::Sum(variants) if variants.vec.iter().any(|variant| variant.name.data == name));
+
+                    // `new` of the `ty` types is generated later
+                    let visibility = input.top_level_items.iter().find_map(|item| match item {
+                        Item::Fn(fun) if fun.name.data == name && fun.owner == Some(idx) => Some(fun.visibility),
+                        _ => None
+                    }).or(match (name, &def.kind) {
+                        ("new", RawTypeDefinitionKind::Product(_)) => def.new,
+                        _ => None
+                    });
+
+                    match visibility {
+                        _ if is_variant => (),
+                        Some(Visibility::Private) => errors.push(error(
+                            import.name.span,
+                            format!("the function `{}::{name}` is private", ty.data),
+                            format!("it can be called only by the functions of `{}`", ty.data)
+                        )),
+                        Some(Visibility::Public) => (),
+                        None => errors.push(error(import.name.span, format!("no variant or function named `{name}` in the type `{}`", ty.data), String::from("here")))
+                    }
+                }
+            }
+        }
+
+        for err in errors {
+            input.report(err)
+        }
+    }
 }
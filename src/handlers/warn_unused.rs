@@ -0,0 +1,260 @@
+use crate::*;
+
+///
+/// Warns about the arguments, variables and types that are never used
+/// and about the arms of `match` that are never reached
+///
+pub fn warn_unused(input: &mut ParseInput) {
+    let mut warnings = vec![];
+    let mut constructed = vec![];
+
+    for item in &input.top_level_items {
+        if let Item::Fn(fun) = item {
+            // The instances of generic functions are checked as a part of the generic ones,
+            // the generated functions have no code to check
+            if fun.is_instance() || fun.is_generated {
+                continue
+            }
+
+            let body = match &input.fn_body_bases {
+                FnBodyContainer::Baked(baked) => &baked[fun.body.base_index as usize],
+                _ => unreachable!()
+            };
+
+            let mut finder = UnusedFinder {
+                input,
+                // The receiver of a method is there even if the method does not need it
+                scope: fun.args.iter().enumerate().map(|(idx, arg)| (arg.name, fun.is_method && idx == 0, true)).collect(),
+                unused: vec![],
+                constructed: vec![],
+                unreachable: vec![]
+            };
+
+            finder.block(&body.body);
+            finder.leave(0);
+
+            constructed.extend(finder.constructed);
+
+            warnings.extend(finder.unreachable.into_iter().map(|span| Diagnostic::new(
+                Severity::Warning,
+                span,
+                String::from("unreachable pattern"),
+                String::from("the arms above match every value this one does")
+            )));
+
+            warnings.extend(finder.unused.into_iter().map(|(name, is_arg)| Diagnostic::new(
+                Severity::Warning,
+                name.span,
+                format!("unused {} `{}`", if is_arg { "argument" } else { "variable" }, name.data),
+                String::from("never used")
+            )))
+        }
+    }
+
+    let baked = match &input.type_bases {
+        TypeBaseContainer::Baked(baked) => baked,
+        _ => unreachable!()
+    };
+
+    let mut used = vec![false; baked.len()];
+
+    for ty in constructed {
+        mark_used(&TypeIndex::Scalar(ty), baked, &mut used)
+    }
+
+    for item in &input.top_level_items {
+        if let Item::Fn(fun) = item {
+            // Generating `new` for a type does not use it
+            if fun.is_generated {
+                continue
+            }
+
+            for ty in fun.args.iter().map(|arg| &arg.ty).chain(core::iter::once(&fun.ret_ty)) {
+                mark_used(ty, baked, &mut used)
+            }
+        }
+    }
+
+    for (idx, base) in baked.iter().enumerate() {
+        let contained: Vec <&TypeIndex> = match &base.kind {
+            BakedTypeBaseKind::Builtin(_) => vec![],
+            BakedTypeBaseKind::TypeProduct(fields) => fields.vec.iter().map(|field| &field.ty).collect(),
+            BakedTypeBaseKind::TypeSum(variants) => variants.vec.iter().flat_map(|variant| &variant.payload).collect()
+        };
+
+        for ty in contained {
+            // A type containing itself does not count as a usage
+            if ty.base().map(|(base, _)| base) != Some(idx as u32) {
+                mark_used(ty, baked, &mut used)
+            }
+        }
+    }
+
+    for (base, used) in baked.iter().zip(used) {
+        if !used && !matches!(base.kind, BakedTypeBaseKind::Builtin(_)) && base.instance_of.is_none() {
+            warnings.push(Diagnostic::new(
+                Severity::Warning,
+                base.name.span,
+                format!("unused type `{}`", base.name.data),
+                String::from("defined here, but never used")
+            ))
+        }
+    }
+
+    for warning in warnings {
+        input.report(warning)
+    }
+}
+
+fn mark_used(ty: &TypeIndex, baked: &[BakedTypeBase], used: &mut [bool]) {
+    match ty {
+        TypeIndex::Scalar(idx) => {
+            used[*idx as usize] = true;
+
+            // Using an instance of a generic type is using the generic type
+            if let Some((generic, _)) = &baked[*idx as usize].instance_of {
+                used[*generic as usize] = true
+            }
+        },
+        TypeIndex::Generic(_) => (),
+        TypeIndex::Tuple(types) => for ty in types {
+            mark_used(ty, baked, used)
+        },
+        TypeIndex::Applied(idx, types) => {
+            used[*idx as usize] = true;
+
+            for ty in types {
+                mark_used(ty, baked, used)
+            }
+        }
+    }
+}
+
+struct UnusedFinder <'input, 'code> {
+    input: &'input ParseInput <'code>,
+
+    /// The visible variables -- their names, whether they are used and whether they are arguments
+    scope: Vec <(Spanned <&'code str>, bool, bool)>,
+
+    /// The unused variables that went out of scope and whether they are arguments
+    unused: Vec <(Spanned <&'code str>, bool)>,
+
+    /// The indices of the types whose values are created
+    constructed: Vec <u32>,
+
+    /// The patterns of the arms of `match` that are never reached
+    unreachable: Vec <Span>
+}
+
+impl <'input, 'code> UnusedFinder <'input, 'code> {
+    /// Makes the variables starting with the `len`th one go out of scope
+    fn leave(&mut self, len: usize) {
+        for (name, used, is_arg) in self.scope.drain(len..) {
+            if !used {
+                self.unused.push((name, is_arg))
+            }
+        }
+    }
+
+    fn block(&mut self, stmts: &[BakedFnBodyStmt <'code>]) {
+        let len = self.scope.len();
+
+        for stmt in stmts {
+            match stmt {
+                BakedFnBodyStmt::Expr(expr) => self.expr(expr),
+                BakedFnBodyStmt::Binding(binding) => {
+                    self.expr(&binding.value);
+                    self.bind(&binding.pattern)
+                }
+            }
+        }
+
+        self.leave(len)
+    }
+
+    fn bind(&mut self, pattern: &BindingPattern <'code>) {
+        match pattern {
+            BindingPattern::Name(name) => self.scope.push((*name, false, false)),
+            BindingPattern::Tuple(patterns, _) => for pattern in patterns {
+                self.bind(pattern)
+            }
+        }
+    }
+
+    fn bind_pattern(&mut self, pattern: &Pattern <'code>) {
+        match pattern {
+            Pattern::Binding(name) => self.scope.push((*name, false, false)),
+            Pattern::Wildcard(_) | Pattern::Int(_) | Pattern::Bool(_) => (),
+            Pattern::Tuple(patterns, _) | Pattern::Variant { args: patterns, .. } | Pattern::Product { fields: patterns, .. } => for pattern in patterns {
+                self.bind_pattern(pattern)
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &dyn AsExprNode <'code>) {
+        match expr.node() {
+            ExprNode::Unary { value, .. } => self.expr(value),
+            ExprNode::Binary { left, right, .. } => {
+                self.expr(left);
+                self.expr(right)
+            },
+            ExprNode::Call(call) => {
+                // Calling the generated `new` of a type creates its value
+                let fun = call.get_fun(self.input);
+                if let (true, Some(owner)) = (fun.is_generated, fun.owner) {
+                    self.constructed.push(owner)
+                }
+
+                for arg in &call.args {
+                    self.expr(arg)
+                }
+            },
+            ExprNode::Try(try_expr) => self.expr(&try_expr.value),
+            ExprNode::Primitive(primitive) => match primitive {
+                PrimitiveExpr::Ident(name) => if let Some((_, used, _)) = self.scope.iter_mut().rev().find(|(x, _, _)| x.data == name.data) {
+                    *used = true
+                },
+                PrimitiveExpr::Int(_) | PrimitiveExpr::Bool(_) => (),
+                PrimitiveExpr::If(if_expr) => {
+                    for branch in &if_expr.branches {
+                        self.expr(&branch.condition);
+                        self.block(&branch.body.body)
+                    }
+                    if let Some(otherwise) = &if_expr.otherwise {
+                        self.block(&otherwise.body)
+                    }
+                },
+                PrimitiveExpr::Match(match_expr) => {
+                    self.expr(&match_expr.value);
+
+                    for arm in &match_expr.arms {
+                        let len = self.scope.len();
+                        self.bind_pattern(&arm.pattern);
+                        self.block(&arm.body.body);
+                        self.leave(len)
+                    }
+
+                    self.unreachable.extend(match_expr.unreachable_arms(self.input).map(|arm| arm.pattern.span()))
+                },
+                PrimitiveExpr::Braced(braced) => self.expr(&braced.value),
+                PrimitiveExpr::Tuple(tuple) => for value in &tuple.value {
+                    self.expr(value)
+                },
+                PrimitiveExpr::Variant(variant) => {
+                    self.constructed.push(variant.ty);
+                    for arg in &variant.args {
+                        self.expr(arg)
+                    }
+                },
+                PrimitiveExpr::Product(product) => {
+                    self.constructed.push(product.ty);
+                    for arg in &product.args {
+                        self.expr(arg)
+                    }
+                },
+                PrimitiveExpr::Field(field) => self.expr(&field.value),
+                PrimitiveExpr::MethodCall(_) => unreachable!("method calls are seen as calls")
+            }
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use crate::*;
+
+/// The size of the stack the program runs with in [`interpret`]
+pub const DEFAULT_STACK_SIZE: usize = 64 * 1024 * 1024;
+
+/// The part of the stack a call is not started in, it is left for evaluating
+/// the expressions of the deepest call and for reporting the overflow
+const STACK_RESERVE_DIVISOR: usize = 8;
+
+///
+/// The tree-walking interpreter over the baked items
+///
+pub struct Interpreter <'input, 'code> {
+    input: &'input ParseInput <'code>,
+
+    /// Variables of the current call, the latest ones go last
+    variables: Vec <(&'code str, Value)>,
+
+    /// The position of the stack where the program started, see [`stack_position`]
+    stack_base: usize,
+
+    /// How much of the stack the calls may take, a call past it is reported as a stack overflow
+    stack_limit: usize
+}
+
+///
+/// How the evaluation of an expression or a block ended
+///
+pub enum Flow {
+    /// The expression has a value, the evaluation goes on
+    Value(Value),
+
+    /// `?` returned the value from the current call, the rest of its body is skipped
+    Return(Value)
+}
+
+/// Evaluates an expression or a block, passing the value returned by `?` to the caller
+macro_rules! value {
+    ($flow:expr) => {
+        match $flow? {
+            Flow::Value(value) => value,
+            returned => return Result(Ok(returned))
+        }
+    };
+}
+
+///
+/// Runs the `main` function of the baked `input`
+///
+/// Returns the value `main` returned
+///
+/// The program runs with a stack of [`DEFAULT_STACK_SIZE`], see [`interpret_with_stack_size`]
+///
+pub fn interpret(input: &ParseInput) -> Result <Value> {
+    interpret_with_stack_size(input, DEFAULT_STACK_SIZE)
+}
+
+///
+/// Runs the `main` function of the baked `input` on its own thread with a stack of `stack_size` bytes
+///
+/// The stack taken by the calls is measured as they are made, so too deep recursion is reported
+/// as an error instead of aborting the host, whatever the size of the frames of the build is
+///
+pub fn interpret_with_stack_size(input: &ParseInput, stack_size: usize) -> Result <Value> {
+    std::thread::scope(|scope| std::thread::Builder::new()
+        .name(String::from("interpreter"))
+        .stack_size(stack_size)
+        .spawn_scoped(scope, || interpret_on_this_thread(input, stack_size - stack_size / STACK_RESERVE_DIVISOR))
+        .expect("failed to spawn the interpreter thread")
+        .join()
+        .unwrap_or_else(|payload| std::panic::resume_unwind(payload)))
+}
+
+/// Returns the address of a local variable, the distance between two of them is the stack taken between
+#[inline(never)]
+fn stack_position() -> usize {
+    let marker = 0u8;
+    core::hint::black_box(&marker) as *const u8 as usize
+}
+
+fn interpret_on_this_thread(input: &ParseInput, stack_limit: usize) -> Result <Value> {
+    let (idx, main) = match input.top_level_items.iter().enumerate().find_map(|(idx, item)| match item {
+        Item::Fn(fun) if fun.name.data == "main" && fun.owner.is_none() && fun.module == 0 => Some((idx, fun)),
+        _ => None
+    }) {
+        Some(x) => x,
+        None => return Result(Err(Error {
+            span: Span {
+                file: input.modules[0].file,
+                ..Span::DEFAULT
+            },
+            message: String::from("no `main` function found"),
+            clarifying: String::from("help: add a function named `main` with no arguments")
+        }))
+    };
+
+    if !main.args.is_empty() {
+        return Result(Err(Error {
+            span: main.name.span,
+            message: String::from("`main` function cannot have arguments"),
+            clarifying: String::from("here")
+        }))
+    }
+
+    if !main.type_params.is_empty() {
+        return Result(Err(Error {
+            span: main.name.span,
+            message: String::from("`main` function cannot have type parameters"),
+            clarifying: String::from("here")
+        }))
+    }
+
+    Interpreter {
+        input,
+        variables: vec![],
+        stack_base: stack_position(),
+        stack_limit
+    }.call(idx, vec![], main.name.span)
+}
+
+impl <'input, 'code> Interpreter <'input, 'code> {
+    fn error <T> (&self, span: Span, message: &str) -> Result <T> {
+        Result(Err(Error {
+            span,
+            message: message.to_string(),
+            clarifying: String::from("happened at runtime here")
+        }))
+    }
+
+    /// Calls the function with the index `fun` in `ParseInput.top_level_items`
+    pub fn call(&mut self, fun: usize, args: Vec <Value>, span: Span) -> Result <Value> {
+        let fun = match &self.input.top_level_items[fun] {
+            Item::Fn(fun) => fun,
+            _ => unreachable!()
+        };
+
+        let body = match &self.input.fn_body_bases {
+            FnBodyContainer::Baked(baked) => &baked[fun.body.base_index as usize],
+            _ => unreachable!()
+        };
+
+        // The stack grows down on most of the platforms, but not on all of them
+        if stack_position().abs_diff(self.stack_base) > self.stack_limit {
+            return self.error(span, "stack overflow")
+        }
+
+        let variables = core::mem::replace(&mut self.variables, fun.args.iter().map(|arg| arg.name.data).zip(args).collect());
+
+        let result = self.block(&body.body).0.map(|flow| match flow {
+            Flow::Value(value) | Flow::Return(value) => value
+        });
+
+        self.variables = variables;
+
+        Result(result)
+    }
+
+    /// Evaluates the statements one by one, returning the value of the last one
+    pub fn block(&mut self, stmts: &[BakedFnBodyStmt <'code>]) -> Result <Flow> {
+        let len = self.variables.len();
+
+        let mut result = Value::UNIT;
+
+        for stmt in stmts {
+            result = match stmt {
+                BakedFnBodyStmt::Expr(expr) => value!(self.expr(expr)),
+                BakedFnBodyStmt::Binding(binding) => {
+                    let value = value!(self.expr(&binding.value));
+                    self.bind(&binding.pattern, value);
+                    Value::UNIT
+                }
+            }
+        }
+
+        // Variables bound inside of the block are not visible outside
+        self.variables.truncate(len);
+
+        Result(Ok(Flow::Value(result)))
+    }
+
+    fn bind(&mut self, pattern: &BindingPattern <'code>, value: Value) {
+        match (pattern, value) {
+            (BindingPattern::Name(name), value) => self.variables.push((name.data, value)),
+            (BindingPattern::Tuple(patterns, _), Value::Tuple(values)) => for (pattern, value) in patterns.iter().zip(values) {
+                self.bind(pattern, value)
+            },
+            _ => unreachable!()
+        }
+    }
+
+    /// Checks whether `value` matches `pattern`, binding the variables of the pattern if so
+    fn matches(&mut self, pattern: &Pattern <'code>, value: &Value) -> bool {
+        match (pattern, value) {
+            (Pattern::Wildcard(_), _) => true,
+            (Pattern::Binding(name), value) => {
+                self.variables.push((name.data, value.clone()));
+                true
+            },
+            (Pattern::Int(int), value) => *value == Value::int(IntExpr::TYPE, int.data as u128),
+            (Pattern::Bool(bool), value) => *value == Value::Bool(bool.data),
+            (Pattern::Tuple(patterns, _), Value::Tuple(values))
+            | (Pattern::Product { fields: patterns, .. }, Value::Product { fields: values, .. }) => patterns
+                .iter()
+                .zip(values)
+                .all(|(pattern, value)| self.matches(pattern, value)),
+            (Pattern::Variant { variant, args, .. }, Value::Sum { variant: actual, payload, .. }) => variant == actual && args
+                .iter()
+                .zip(payload)
+                .all(|(pattern, value)| self.matches(pattern, value)),
+            _ => unreachable!()
+        }
+    }
+
+    pub fn expr(&mut self, expr: &dyn AsExprNode <'code>) -> Result <Flow> {
+        Result(Ok(Flow::Value(match expr.node() {
+            ExprNode::Unary { op, value, span } => match value!(self.expr(value)).perform_unary_operation(op) {
+                Ok(ok) => ok,
+                Err(message) => return self.error(span, message)
+            },
+            ExprNode::Binary { op, left, right, span } => {
+                let left = value!(self.expr(left));
+
+                // Short-circuit the logical operators
+                if let (BinaryOperator::And, Value::Bool(false)) | (BinaryOperator::Or, Value::Bool(true)) = (op, &left) {
+                    return Result(Ok(Flow::Value(left)))
+                }
+
+                let right = value!(self.expr(right));
+
+                match left.perform_binary_operation(op, right) {
+                    Ok(ok) => ok,
+                    Err(message) => return self.error(span, message)
+                }
+            },
+            ExprNode::Call(call) => {
+                let mut args = Vec::with_capacity(call.args.len());
+                for arg in &call.args {
+                    args.push(value!(self.expr(arg)))
+                }
+
+                self.call(call.fun, args, call.span)?
+            },
+            ExprNode::Try(try_expr) => {
+                let value = value!(self.expr(&try_expr.value));
+
+                match self.call(try_expr.fun, vec![value], try_expr.span())? {
+                    Value::Sum { variant: TryExprFull::CONTINUE, mut payload, .. } => payload.remove(0),
+                    Value::Sum { mut payload, .. } => return Result(Ok(Flow::Return(payload.remove(0)))),
+                    _ => unreachable!()
+                }
+            },
+            ExprNode::Primitive(primitive) => match primitive {
+                PrimitiveExpr::Ident(name) => self.variables
+                    .iter()
+                    .rev()
+                    .find(|(variable, _)| *variable == name.data)
+                    .expect("variables are checked to exist during the baking")
+                    .1
+                    .clone(),
+                PrimitiveExpr::Int(int) => Value::int(IntExpr::TYPE, int.value as u128),
+                PrimitiveExpr::Bool(bool) => Value::Bool(bool.data),
+                PrimitiveExpr::If(if_expr) => {
+                    for branch in &if_expr.branches {
+                        if value!(self.expr(&branch.condition)) == Value::Bool(true) {
+                            return self.block(&branch.body.body)
+                        }
+                    }
+
+                    match &if_expr.otherwise {
+                        Some(otherwise) => value!(self.block(&otherwise.body)),
+                        None => Value::UNIT
+                    }
+                },
+                PrimitiveExpr::Match(match_expr) => {
+                    let value = value!(self.expr(&match_expr.value));
+                    let len = self.variables.len();
+
+                    for arm in &match_expr.arms {
+                        if self.matches(&arm.pattern, &value) {
+                            let result = self.block(&arm.body.body);
+                            self.variables.truncate(len);
+                            return result
+                        }
+
+                        self.variables.truncate(len)
+                    }
+
+                    unreachable!("`match` is checked to be exhaustive during the baking")
+                },
+                PrimitiveExpr::Braced(braced) => value!(self.expr(&braced.value)),
+                PrimitiveExpr::Tuple(tuple) => {
+                    let mut values = Vec::with_capacity(tuple.value.len());
+                    for expr in &tuple.value {
+                        values.push(value!(self.expr(expr)))
+                    }
+
+                    Value::Tuple(values)
+                },
+                PrimitiveExpr::Variant(variant) => {
+                    let mut payload = Vec::with_capacity(variant.args.len());
+                    for arg in &variant.args {
+                        payload.push(value!(self.expr(arg)))
+                    }
+
+                    Value::Sum {
+                        ty: variant.ty,
+                        variant: variant.variant,
+                        payload
+                    }
+                },
+                PrimitiveExpr::Product(product) => {
+                    let mut fields = Vec::with_capacity(product.args.len());
+                    for arg in &product.args {
+                        fields.push(value!(self.expr(arg)))
+                    }
+
+                    Value::Product {
+                        ty: product.ty,
+                        fields
+                    }
+                },
+                PrimitiveExpr::Field(field) => match value!(self.expr(&field.value)) {
+                    Value::Product { mut fields, .. } => fields.swap_remove(field.field),
+                    _ => unreachable!()
+                },
+                PrimitiveExpr::MethodCall(_) => unreachable!("method calls are seen as calls")
+            }
+        })))
+    }
+}
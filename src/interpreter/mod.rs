@@ -0,0 +1 @@
+crate::modules!(value eval);
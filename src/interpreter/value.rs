@@ -0,0 +1,165 @@
+use crate::*;
+use core::fmt::{Debug, Formatter, Write, Result as FmtResult};
+
+macro_rules! value {
+    (ints: [$( $int:ident($int_ty:ty) )*] floats: [$( $float:ident($float_ty:ty) )*]) => {
+        ///
+        /// The value an Oko expression evaluates to at runtime
+        ///
+        #[derive(Clone, PartialEq)]
+        pub enum Value {
+            $( $int($int_ty), )*
+            $( $float($float_ty), )*
+            Bool(bool),
+            Tuple(Vec <Value>),
+
+            /// The value of a `ty` type, fields go in the order of their declaration
+            Product {
+                /// The index of the type in the type bases container
+                ty: u32,
+                fields: Vec <Value>
+            },
+
+            /// The value of an `enum` type
+            Sum {
+                /// The index of the type in the type bases container
+                ty: u32,
+
+                /// The index of the variant in the enum
+                variant: usize,
+                payload: Vec <Value>
+            }
+        }
+
+        impl Value {
+            /// Creates a value of the builtin integer type named `ty`
+            ///
+            /// `value` should already be checked to fit into `ty`
+            pub fn int(ty: &str, value: u128) -> Self {
+                match ty {
+                    $( stringify!($int_ty) => Self::$int(value as $int_ty), )*
+                    _ => unreachable!()
+                }
+            }
+
+            /// Applies `op` to `self`
+            ///
+            /// Returns the message on failure
+            pub fn perform_unary_operation(self, op: UnaryOperator) -> core::result::Result <Value, &'static str> {
+                Ok(match (op, self) {
+                    (UnaryOperator::Pos, value) => value,
+                    $( (UnaryOperator::Neg, Self::$int(value)) => Self::$int(value.checked_neg().ok_or("attempt to negate with overflow")?), )*
+                    $( (UnaryOperator::Neg, Self::$float(value)) => Self::$float(-value), )*
+                    (UnaryOperator::Not, Self::Bool(value)) => Self::Bool(!value),
+                    _ => unreachable!()
+                })
+            }
+
+            /// Applies `op` to `self` and `operand`
+            ///
+            /// Returns the message on failure
+            pub fn perform_binary_operation(self, op: BinaryOperator, operand: Value) -> core::result::Result <Value, &'static str> {
+                Ok(match (self, operand) {
+                    $( (Self::$int(left), Self::$int(right)) => match op {
+                        BinaryOperator::Mul => Self::$int(left.checked_mul(right).ok_or("attempt to multiply with overflow")?),
+                        BinaryOperator::Div => if right == 0 {
+                            return Err("attempt to divide by zero")
+                        } else {
+                            Self::$int(left.checked_div(right).ok_or("attempt to divide with overflow")?)
+                        },
+                        BinaryOperator::Add => Self::$int(left.checked_add(right).ok_or("attempt to add with overflow")?),
+                        BinaryOperator::Sub => Self::$int(left.checked_sub(right).ok_or("attempt to subtract with overflow")?),
+                        op => Self::Bool(value!(@compare op, left, right))
+                    }, )*
+                    $( (Self::$float(left), Self::$float(right)) => match op {
+                        BinaryOperator::Mul => Self::$float(left * right),
+                        BinaryOperator::Div => Self::$float(left / right),
+                        BinaryOperator::Add => Self::$float(left + right),
+                        BinaryOperator::Sub => Self::$float(left - right),
+                        op => Self::Bool(value!(@compare op, left, right))
+                    }, )*
+                    (Self::Bool(left), Self::Bool(right)) => Self::Bool(match op {
+                        BinaryOperator::And => left && right,
+                        BinaryOperator::Or => left || right,
+                        op => value!(@compare op, left, right)
+                    }),
+                    _ => unreachable!()
+                })
+            }
+        }
+
+        impl ParseDebug for Value {
+            fn debug_impl(&self, input: &ParseInput, f: &mut Formatter <'_>) -> FmtResult {
+                match self {
+                    $( Self::$int(value) => Debug::fmt(value, f), )*
+                    $( Self::$float(value) => Debug::fmt(value, f), )*
+                    Self::Bool(value) => Debug::fmt(value, f),
+                    Self::Tuple(values) => {
+                        f.write_char('(')?;
+                        print_punctuated_seq::<_, ", ">(values.iter().map(|x| x.debug(input)), f)?;
+                        if values.len() == 1 {
+                            f.write_char(',')?;
+                        }
+                        f.write_char(')')
+                    },
+                    Self::Product { ty, fields } => match &input.type_bases {
+                        TypeBaseContainer::Baked(baked) => {
+                            let base = &baked[*ty as usize];
+                            let mut builder = f.debug_struct(base.name.data);
+                            if let BakedTypeBaseKind::TypeProduct(names) = &base.kind {
+                                for (name, value) in names.vec.iter().zip(fields) {
+                                    builder.field(name.name.data, &value.debug(input));
+                                }
+                            }
+                            builder.finish()
+                        },
+                        _ => unreachable!()
+                    },
+                    Self::Sum { ty, variant, payload } => match &input.type_bases {
+                        TypeBaseContainer::Baked(baked) => match &baked[*ty as usize].kind {
+                            BakedTypeBaseKind::TypeSum(variants) => {
+                                let name = variants.vec[*variant].name.data;
+                                if payload.is_empty() {
+                                    return f.write_str(name)
+                                }
+                                let mut builder = f.debug_tuple(name);
+                                for value in payload {
+                                    builder.field(&value.debug(input));
+                                }
+                                builder.finish()
+                            },
+                            _ => unreachable!()
+                        },
+                        _ => unreachable!()
+                    }
+                }
+            }
+        }
+    };
+
+    (@compare $op:ident, $left:ident, $right:ident) => {
+        match $op {
+            BinaryOperator::Eq => $left == $right,
+            BinaryOperator::Ne => $left != $right,
+            BinaryOperator::Le => $left <= $right,
+            BinaryOperator::Ge => $left >= $right,
+            BinaryOperator::Lt => $left < $right,
+            BinaryOperator::Gt => $left > $right,
+            _ => unreachable!()
+        }
+    };
+}
+
+value! {
+    ints: [
+        I8(i8) I16(i16) I32(i32) I64(i64) I128(i128) Isize(isize)
+        U8(u8) U16(u16) U32(u32) U64(u64) U128(u128) Usize(usize)
+    ]
+    floats: [
+        F32(f32) F64(f64)
+    ]
+}
+
+impl Value {
+    pub const UNIT: Value = Value::Tuple(vec![]);
+}
@@ -5,7 +5,10 @@
 
 #![allow(incomplete_features)]
 
-modules!(token ast span error handlers);
+// The tokens, bodies and items refer to each other by borrows of the token arena and by indices
+#![forbid(unsafe_code)]
+
+modules!(token ast span source_map error handlers interpreter backend);
 
 pub const SPACES_IN_TAB: u32 = 4;
 
@@ -1,32 +1,263 @@
+#![forbid(unsafe_code)]
+
 use oko::*;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: oko <COMMAND> [OPTIONS] <FILES>...
+
+Commands:
+    check          Check the files for errors
+    run            Run the `main` function of the file
+    build          Translate the files into C
+    dump-tokens    Print the tokens of the files
+    dump-ast       Print the baked programs of the files
+
+Options:
+    -o <FILE>           Write the C code of `build` into <FILE> instead of <INPUT>.c
+    --color <WHEN>      Color the diagnostics: `auto`, `always` or `never`
+    --indent <UNIT>     One level of indentation: `auto`, `tab` or a number of spaces,
+                        `auto` takes the indentation of the first indented line of each file
+    --stack-size <MIB>  The stack `run` gives the program in mebibytes, 64 by default,
+                        deeper recursion is reported as a stack overflow
+    -h, --help          Print this message
+
+Exit codes:
+    0      Success
+    1      The files contain errors or cannot be read or written
+    2      Wrong command line arguments
+    101    A runtime error happened in `run`";
+
+/// Exit code for wrong command line arguments
+const USAGE_ERROR: u8 = 2;
+
+/// Exit code for runtime errors, the same the generated C code uses
+const RUNTIME_ERROR: u8 = 101;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Command {
+    Check,
+    Run,
+    Build,
+    DumpTokens,
+    DumpAst
+}
+
+struct Cli {
+    command: Command,
+    inputs: Vec <PathBuf>,
+    output: Option <PathBuf>,
+    colored: bool,
+    indent: IndentUnit,
+
+    /// The stack of `run` in bytes
+    stack_size: usize
+}
+
+impl Cli {
+    fn parse(mut args: impl Iterator <Item = String>) -> core::result::Result <Self, String> {
+        let command = match args.next().as_deref() {
+            Some("check") => Command::Check,
+            Some("run") => Command::Run,
+            Some("build") => Command::Build,
+            Some("dump-tokens") => Command::DumpTokens,
+            Some("dump-ast") => Command::DumpAst,
+            Some(other) => return Err(format!("unknown command `{other}`")),
+            None => return Err(String::from("no command given"))
+        };
+
+        let mut inputs = vec![];
+        let mut output = None;
+        let mut colored = None;
+        let mut indent = IndentUnit::Detect;
+        let mut stack_size = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-o" => match args.next() {
+                    Some(path) if output.is_none() => output = Some(PathBuf::from(path)),
+                    Some(_) => return Err(String::from("`-o` given more than once")),
+                    None => return Err(String::from("`-o` requires a file"))
+                },
+                "--color" => colored = Some(match args.next().as_deref() {
+                    Some("always") => true,
+                    Some("never") => false,
+                    Some("auto") => Self::auto_colored(),
+                    _ => return Err(String::from("`--color` requires one of `auto`, `always` or `never`"))
+                }),
+                "--indent" => indent = match args.next().as_deref() {
+                    Some("auto") => IndentUnit::Detect,
+                    Some("tab") => IndentUnit::Tab,
+                    Some(spaces) => match spaces.parse() {
+                        Ok(spaces) if spaces > 0 => IndentUnit::Spaces(spaces),
+                        _ => return Err(format!("unknown indentation unit `{spaces}`, expected `auto`, `tab` or a positive number of spaces"))
+                    },
+                    None => return Err(String::from("`--indent` requires one of `auto`, `tab` or a number of spaces"))
+                },
+                "--stack-size" => stack_size = match args.next().as_deref().map(str::parse::<usize>) {
+                    Some(Ok(mebibytes)) if mebibytes > 0 && mebibytes <= usize::MAX >> 20 => Some(mebibytes << 20),
+                    _ => return Err(String::from("`--stack-size` requires a positive number of mebibytes"))
+                },
+                _ if arg.starts_with('-') => return Err(format!("unknown option `{arg}`")),
+                _ => inputs.push(PathBuf::from(arg))
+            }
+        }
+
+        if inputs.is_empty() {
+            return Err(String::from("no input files given"))
+        }
+
+        if output.is_some() && command != Command::Build {
+            return Err(String::from("`-o` is only allowed for `build`"))
+        }
+
+        if stack_size.is_some() && command != Command::Run {
+            return Err(String::from("`--stack-size` is only allowed for `run`"))
+        }
+
+        if inputs.len() > 1 && (command == Command::Run || output.is_some()) {
+            return Err(String::from("exactly one input file is required for `run` and `build -o`"))
+        }
+
+        Ok(Self {
+            command,
+            inputs,
+            output,
+            colored: colored.unwrap_or_else(Self::auto_colored),
+            indent,
+            stack_size: stack_size.unwrap_or(DEFAULT_STACK_SIZE)
+        })
+    }
 
-fn main() -> Result <()> {
-    let code = std::fs::read_to_string("code")
-        .expect("failed to find `code`")
-        // Make 4-space combinations be a tab
-        .replace("    ", "\t");
+    fn auto_colored() -> bool {
+        std::env::var_os("NO_COLOR").is_none() && std::io::stderr().is_terminal()
+    }
+}
+
+fn main() -> ExitCode {
+    set_colored(Cli::auto_colored());
+
+    let mut args = std::env::args().skip(1).peekable();
+
+    match args.peek().map(String::as_str) {
+        Some("-h" | "--help") => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS
+        },
+        None => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(USAGE_ERROR)
+        },
+        _ => ()
+    }
+
+    let cli = match Cli::parse(args) {
+        Ok(ok) => ok,
+        Err(err) => {
+            report_error_message(&format!("{err}, see `oko --help`"));
+            return ExitCode::from(USAGE_ERROR)
+        }
+    };
+
+    set_colored(cli.colored);
+
+    let mut exit_code = ExitCode::SUCCESS;
+
+    for path in &cli.inputs {
+        let code = process(&cli, path);
+
+        if code != ExitCode::SUCCESS {
+            exit_code = code
+        }
+    }
 
-    let code = remove_non_doc_comments(code);
+    exit_code
+}
+
+fn process(cli: &Cli, path: &Path) -> ExitCode {
+    let code = match std::fs::read_to_string(path) {
+        Ok(ok) => prepare_code(&ok),
+        Err(err) => return report_error_message(&format!("failed to read `{}`: {err}", path.display()))
+    };
+
+    // Every input file is a separate program with its own files
+    let mut source_map = SourceMap::new();
+
+    let root = SourceFile {
+        indent: cli.indent,
+        ..SourceFile::new(&mut source_map, vec![], path.to_string_lossy().into_owned(), code)
+    };
 
-    let stream = TokenStream::new("code", &code)?;
-    let stream = TokenStream::from(&stream);
+    if cli.command == Command::DumpTokens {
+        return match TokenStream::new(root.file, &root.code, root.indent).0 {
+            Ok(tokens) => {
+                for token in &tokens {
+                    println!("{:?} {token:?}", token.span.start)
+                }
+                ExitCode::SUCCESS
+            },
+            Err(err) => err.report(&source_map)
+        }
+    }
 
-    let mut input = ParseInput {
-        stream,
-        code: &code,
-        filename: "code",
-        type_bases: TypeBaseContainer::new(),
-        fn_body_bases: FnBodyContainer::new(),
-        top_level_items: vec![]
+    let files = match load_modules(&mut source_map, root, path).0 {
+        Ok(ok) => ok,
+        Err(err) => return err.report(&source_map)
     };
-    
-    parse_code(&mut input)?;
 
-    bake_raw_types(&mut input)?;
+    let result = compile_program(&source_map, &files, |input| {
+        report_diagnostics(&input.diagnostics, input.source_map);
+
+        match cli.command {
+            Command::Check => ExitCode::SUCCESS,
+            Command::Run => match interpret_with_stack_size(input, cli.stack_size).0 {
+                Ok(value) => {
+                    if !matches!(&value, Value::Tuple(values) if values.is_empty()) {
+                        println!("{:?}", value.debug(input))
+                    }
+                    ExitCode::SUCCESS
+                },
+                Err(err) => {
+                    err.report(input.source_map);
+                    ExitCode::from(RUNTIME_ERROR)
+                }
+            },
+            Command::Build => {
+                let output = cli.output.clone().unwrap_or_else(|| path.with_extension("c"));
+
+                match write_c(input, &output) {
+                    Ok(()) => ExitCode::SUCCESS,
+                    Err(err) => report_error_message(&format!("failed to write `{}`: {err}", output.display()))
+                }
+            },
+            Command::DumpAst => {
+                println!("{input:#?}");
+                ExitCode::SUCCESS
+            },
+            Command::DumpTokens => unreachable!()
+        }
+    });
+
+    match result.0 {
+        Ok(code) => code,
+        Err(diagnostics) => {
+            report_diagnostics(&diagnostics, &source_map);
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    bake_fn_bodies(&mut input)?;
+/// Prints the diagnostics with the lines from the `source_map`, followed by the amount of errors if there is more than one
+fn report_diagnostics(diagnostics: &[Diagnostic], source_map: &SourceMap) {
+    for diagnostic in diagnostics {
+        diagnostic.render(source_map)
+    }
 
-    println!("{input:#?}");
+    let errors = diagnostics.iter().filter(|diagnostic| diagnostic.is_error()).count();
 
-    Result(Ok(()))
+    if errors > 1 {
+        report_error_message(&format!("aborting due to {errors} errors"));
+    }
 }
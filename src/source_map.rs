@@ -0,0 +1,77 @@
+///
+/// The index of a file in the source map, every span knows the file it is in
+///
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct FileId(u32);
+
+impl FileId {
+    /// The file of the spans that are not in any file, like the ones of the builtin types
+    pub const DUMMY: Self = Self(u32::MAX);
+}
+
+///
+/// A source file the spans point into
+///
+pub struct SourceMapFile {
+    pub name: String,
+    code: String,
+
+    /// The offsets of the starts of the lines
+    line_starts: Vec <u32>
+}
+
+impl SourceMapFile {
+    /// Returns the offset of the start of the line `line`, which starts from 1
+    pub fn line_start(&self, line: u32) -> u32 {
+        self.line_starts[line as usize - 1]
+    }
+
+    /// Returns the line `line`, which starts from 1, with no line break
+    pub fn line(&self, line: u32) -> &str {
+        let start = self.line_start(line) as usize;
+
+        match self.line_starts.get(line as usize) {
+            Some(end) => &self.code[start..*end as usize - 1],
+            None => &self.code[start..]
+        }
+    }
+}
+
+///
+/// The source files of one compilation, so that a diagnostic can be rendered knowing only its spans
+///
+/// The ids are given in the order the files are added, so the map has to outlive
+/// the diagnostics rendered with it
+///
+#[derive(Default)]
+pub struct SourceMap {
+    files: Vec <SourceMapFile>
+}
+
+impl SourceMap {
+    pub const fn new() -> Self {
+        Self {
+            files: vec![]
+        }
+    }
+
+    /// Adds the file named `name` with the `code`, returning its id
+    pub fn add(&mut self, name: &str, code: &str) -> FileId {
+        let line_starts = core::iter::once(0)
+            .chain(code.match_indices('\n').map(|(idx, _)| idx as u32 + 1))
+            .collect();
+
+        self.files.push(SourceMapFile {
+            name: name.to_string(),
+            code: code.to_string(),
+            line_starts
+        });
+
+        FileId(self.files.len() as u32 - 1)
+    }
+
+    /// Returns the file with the id `file`, `None` for `FileId::DUMMY`
+    pub fn get(&self, file: FileId) -> Option <&SourceMapFile> {
+        self.files.get(file.0 as usize)
+    }
+}
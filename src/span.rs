@@ -1,3 +1,4 @@
+use crate::{FileId, SourceMapFile};
 use core::fmt::{Debug, Result, Formatter};
 
 ///
@@ -13,13 +14,19 @@ pub struct CursorPosition {
     ///
     /// Starts from 1
     ///
-    pub column: u32
+    pub column: u32,
+
+    ///
+    /// The offset in bytes from the start of the file, starts from 0
+    ///
+    pub offset: u32
 }
 
 impl CursorPosition {
     pub const DEFAULT: Self = CursorPosition {
         line: 1,
-        column: 1
+        column: 1,
+        offset: 0
     };
 }
 
@@ -32,6 +39,9 @@ impl Debug for CursorPosition {
 /// The precise position of an item in the file
 #[derive(Copy, Clone)]
 pub struct Span {
+    /// The file the item is in
+    pub file: FileId,
+
     ///
     /// The pointer to first symbol of an item
     ///
@@ -45,35 +55,42 @@ pub struct Span {
 
 impl Span {
     pub const DEFAULT: Span = Span {
+        file: FileId::DUMMY,
         start: CursorPosition::DEFAULT,
         end: CursorPosition::DEFAULT
     };
 }
 
 impl Span {
-    /// Extends the cursor position to span by making the end out of it
-    pub const fn extend_by_one(pos: CursorPosition) -> Self {
+    /// Extends the cursor position in the `file` to span by making the end out of it
+    pub const fn extend_by_one(file: FileId, pos: CursorPosition) -> Self {
         Self {
+            file,
             start: pos,
             end: CursorPosition {
                 line: pos.line,
-                column: pos.column + 1
+                column: pos.column + 1,
+                offset: pos.offset + 1
             }
         }
     }
 
-    /// Returns the lines in `content` that is spanned by `self`
-    pub fn get_spanned_lines(self, code: &str) -> Vec <&str> {
-        let mut lines = code
-            .split('\n')
-            .skip((self.start.line - 1) as usize)
-            .take((self.end.line - self.start.line + 1) as usize)
-            .collect::<Vec <_>>();
+    /// Returns the lines of the `file` that are spanned by `self`
+    pub fn get_spanned_lines(self, file: &SourceMapFile) -> Vec <&str> {
+        let mut lines = (self.start.line..=self.end.line).map(|line| file.line(line)).collect::<Vec <_>>();
 
-        lines[0] = &lines[0][self.start.column as usize - 1..];
+        lines[0] = &lines[0][(self.start.offset - file.line_start(self.start.line)) as usize..];
 
         let last = lines.last_mut().expect("no lines in span");
-        *last = &last[..(self.end.column as usize - 1).min(last.len())];
+        let end = (self.end.offset - file.line_start(self.end.line)) as usize;
+
+        // The first line is cut from the start already
+        let end = match self.start.line == self.end.line {
+            true => end - (self.start.offset - file.line_start(self.start.line)) as usize,
+            false => end
+        };
+
+        *last = &last[..end.min(last.len())];
         if let Some(idx) = last.find(char::is_whitespace) {
             *last = &last[..idx]
         }
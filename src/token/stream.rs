@@ -48,34 +48,179 @@ impl <'code> TokenStream <'code> {
     }
 
     ///
-    /// Creates new vec for [`TokenStream`] from the source code `code` of file named `filename`
+    /// Creates new vec for [`TokenStream`] from the source code `code` of the `file`,
+    /// which should have no carriage returns, see `prepare_code`
     ///
-    pub fn new(filename: &str, code: &'code str) -> Result <Vec <Token <'code>>> {
+    /// The indentation in the beginning of each line is measured in `indent` units
+    /// and becomes [`TokenKind::Indent`] or [`TokenKind::Dedent`] tokens if it changes,
+    /// the lines with nothing but comments do not change it, unless they are doc comments.
+    /// Spaces and tabs anywhere else only separate the tokens
+    ///
+    pub fn new(file: FileId, code: &'code str, mut indent: IndentUnit) -> Result <Vec <Token <'code>>> {
         let mut buf = vec![];
         let mut cursor_position = CursorPosition::DEFAULT;
         let mut remaining_code = code;
+        let mut levels = 0;
+        let mut is_line_start = true;
 
-        remove_spaces(&mut remaining_code, &mut cursor_position);
         while !remaining_code.is_empty() {
-            let token = Token::parse(&mut cursor_position, &mut remaining_code, filename, code)?;
-
-            // Prevent tabs from being emitted if they are not following a newline or an another tab,
-            // i.e. tabs are interpreted as tabs only if they are in the beginning of the line
-            // and there are no other symbols than tabs between this tab and the beginning of the line,
-            // treated as space otherwise
-            if !(token.kind == TokenKind::Tab && buf.last().map(|x: &Token| x.kind != TokenKind::Newline && x.kind != TokenKind::Tab).unwrap_or(false)) {
-                buf.push(token)
+            if is_line_start {
+                is_line_start = false;
+
+                let start = cursor_position;
+                let whitespace = remove_spaces(&mut remaining_code, &mut cursor_position);
+
+                if remaining_code.is_empty() || remaining_code.starts_with('\n') || (remaining_code.starts_with(';') && !remaining_code.starts_with(";;")) {
+                    continue
+                }
+
+                let span = Span {
+                    file,
+                    start,
+                    end: cursor_position
+                };
+
+                let level = indent.level_of(whitespace, span)?;
+
+                if level > levels + 1 {
+                    return Result(Err(Error {
+                        span,
+                        message: String::from("unexpected indentation"),
+                        clarifying: format!("this line is indented by {level} levels, but the previous one only by {levels}")
+                    }))
+                }
+
+                if level > levels {
+                    buf.push(Token {
+                        kind: TokenKind::Indent,
+                        span
+                    })
+                }
+
+                for _ in level..levels {
+                    buf.push(Token {
+                        kind: TokenKind::Dedent,
+                        span: Span {
+                            file,
+                            start: cursor_position,
+                            end: cursor_position
+                        }
+                    })
+                }
+
+                levels = level;
+                continue
+            }
+
+            let token = Token::parse(file, &mut cursor_position, &mut remaining_code)?;
+
+            match token.kind {
+                // Comments are trivia, they only move the cursor so that the spans
+                // of the following tokens still point into the original text
+                TokenKind::Comment(_) => (),
+                TokenKind::DocComment(_) if !matches!(buf.last().map(|token: &Token| &token.kind), None | Some(TokenKind::Newline | TokenKind::Indent | TokenKind::Dedent)) => (),
+                TokenKind::Newline => {
+                    is_line_start = true;
+                    buf.push(token);
+                    continue
+                },
+                _ => buf.push(token)
             }
 
             remove_spaces(&mut remaining_code, &mut cursor_position);
         }
 
+        for _ in 0..levels {
+            buf.push(Token {
+                kind: TokenKind::Dedent,
+                span: Span {
+                    file,
+                    start: cursor_position,
+                    end: cursor_position
+                }
+            })
+        }
+
         Result(Ok(buf))
     }
 }
 
-fn remove_spaces(code: &mut &str, cursor_pos: &mut CursorPosition) {
-    let index = code.find(|char: char| char != ' ').unwrap_or(0);
+///
+/// What one level of indentation is
+///
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum IndentUnit {
+    /// The indentation of the first indented line of the file
+    #[default]
+    Detect,
+
+    /// A tab character
+    Tab,
+
+    /// The number of spaces, never zero
+    Spaces(u32)
+}
+
+impl IndentUnit {
+    ///
+    /// Returns the level of the `whitespace` in the beginning of a line spanned by `span`,
+    /// the unit is detected from it first if it is not known yet
+    ///
+    /// Fails if the `whitespace` has both tabs and spaces or is not a whole number of units
+    ///
+    fn level_of(&mut self, whitespace: &str, span: Span) -> Result <u32> {
+        if whitespace.is_empty() {
+            return Result(Ok(0))
+        }
+
+        if *self == Self::Detect {
+            *self = if whitespace.starts_with('\t') {
+                Self::Tab
+            } else {
+                Self::Spaces(whitespace.find('\t').unwrap_or(whitespace.len()) as u32)
+            }
+        }
+
+        let (own, other, unit) = match *self {
+            Self::Tab => ('\t', ' ', String::from("a tab")),
+            Self::Spaces(spaces) => (' ', '\t', format!("{spaces} spaces")),
+            Self::Detect => unreachable!()
+        };
+
+        if whitespace.contains(other) {
+            let used = if whitespace.contains(own) {
+                "both tabs and spaces"
+            } else if other == ' ' {
+                "spaces"
+            } else {
+                "tabs"
+            };
+
+            return Result(Err(Error {
+                span,
+                message: String::from("mixed indentation"),
+                clarifying: format!("this line is indented with {used}, but the indentation unit is {unit}")
+            }))
+        }
+
+        match *self {
+            Self::Spaces(spaces) if !(whitespace.len() as u32).is_multiple_of(spaces) => Result(Err(Error {
+                span,
+                message: String::from("misaligned indentation"),
+                clarifying: format!("this line is indented by {} spaces, which is not a multiple of the indentation unit of {unit}", whitespace.len())
+            })),
+            Self::Spaces(spaces) => Result(Ok(whitespace.len() as u32 / spaces)),
+            _ => Result(Ok(whitespace.len() as u32))
+        }
+    }
+}
+
+/// Skips the spaces and tabs in the beginning of the `code`, returning them
+fn remove_spaces <'code> (code: &mut &'code str, cursor_pos: &mut CursorPosition) -> &'code str {
+    let index = code.find(|char: char| char != ' ' && char != '\t').unwrap_or(code.len());
+    let spaces = &code[..index];
     *code = &code[index..];
     cursor_pos.column += index as u32;
+    cursor_pos.offset += index as u32;
+    spaces
 }
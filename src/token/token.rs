@@ -1,21 +1,63 @@
 use crate::*;
 use core::fmt::{Debug, Formatter, Result as FmtResult};
 
+///
+/// Returns the length of the comment at the beginning of the `code`,
+/// either a single-line one, which lasts until the end of the line, excluding the newline,
+/// or a multiline one, which may be nested
+///
+/// Returns `None` if there is no comment or the multiline one is never closed
+///
+fn comment_len(code: &str) -> Option <u32> {
+    if !code.starts_with(';') {
+        return None
+    }
+
+    if !code.starts_with(";[") {
+        return Some(code.find('\n').unwrap_or(code.len()) as u32)
+    }
+
+    let mut depth = 0;
+    let mut index = 0;
+    while index < code.len() {
+        let rest = &code[index..];
+        if rest.starts_with(";[") {
+            depth += 1;
+            index += 2
+        } else if rest.starts_with("];") {
+            depth -= 1;
+            index += 2;
+            if depth == 0 {
+                return Some(index as u32)
+            }
+        } else {
+            index += rest.chars().next().map_or(1, char::len_utf8)
+        }
+    }
+
+    None
+}
+
 #[inline]
-fn parse_one <'code, const NEWLINES: u32> (
+fn parse_one <'code> (
+    file: FileId,
     pos: &mut CursorPosition,
     len: u32,
     kind: TokenKind <'code>,
     code: &mut &'code str
 ) -> Token <'code> {
     let oldpos = *pos;
-    if NEWLINES > 0 {
-        pos.line += NEWLINES;
-        pos.column = 1
-    } else {
-        pos.column += len;
+    let text = &(*code)[..len as usize];
+    match text.rfind('\n') {
+        Some(last) => {
+            pos.line += text.matches('\n').count() as u32;
+            pos.column = len - last as u32
+        },
+        None => pos.column += len
     }
+    pos.offset += len;
     let span = Span {
+        file,
         start: oldpos,
         end: *pos
     };
@@ -27,18 +69,34 @@ fn parse_one <'code, const NEWLINES: u32> (
 }
 
 macro_rules! token {
-    ($($( #[$( $attrs:tt )*] )* $name:ident $(($value:ty))? [$fn_name:ident] [$debugname:literal $($tt:tt)* ] )*) => {
+    (
+        $($( #[$( $attrs:tt )*] )* $name:ident $(($value:ty))? [$fn_name:ident] [$debugname:literal $($tt:tt)* ] )*
+        ;
+        $($( #[$( $synthetic_attrs:tt )*] )* $synthetic:ident [$synthetic_fn_name:ident] [$synthetic_debugname:literal] )*
+    ) => {
         ///
         /// The kind of token
         ///
         #[derive(Clone, Eq, PartialEq)]
-        pub enum TokenKind <'code> {$(
-            $( #[$( $attrs )*] )*
-            $name $(($value))?
-        ),*}
+        pub enum TokenKind <'code> {
+            $(
+                $( #[$( $attrs )*] )*
+                $name $(($value))?,
+            )*
+            $(
+                $( #[$( $synthetic_attrs )*] )*
+                $synthetic,
+            )*
+        }
 
         impl <'code> Debug for TokenKind <'code> {
             fn fmt(&self, f: &mut Formatter <'_>) -> FmtResult {
+                $(
+                    if let Self::$synthetic = self {
+                        return f.write_str($synthetic_debugname)
+                    }
+                )*
+
                 token!(@debug f, $( [self $name $($value,)? $debugname $($tt)*] )*)
             }
         }
@@ -53,21 +111,35 @@ macro_rules! token {
             /// Mutates the `code` to exclude the parsed symbols on success
             ///
             /// Returns the parsed token
-            pub fn parse(pos: &mut CursorPosition, code_remaining: &mut &'code str, filename: &str, full_code: &str) -> Result <Self> {
+            pub fn parse(file: FileId, pos: &mut CursorPosition, code_remaining: &mut &'code str) -> Result <Self> {
                 debug_assert!(!code_remaining.is_empty());
 
+                if code_remaining.starts_with(";[") && comment_len(code_remaining).is_none() {
+                    return Result(Err(Error {
+                        span: Span {
+                            file,
+                            start: *pos,
+                            end: CursorPosition {
+                                column: pos.column + 2,
+                                offset: pos.offset + 2,
+                                ..*pos
+                            }
+                        },
+                        message: String::from("unterminated multiline comment"),
+                        clarifying: String::from("this `;[` is never closed with `];`")
+                    }))
+                }
+
                 $(
-                    token!(@parse code_remaining, pos, $name $(($value))? $debugname $($tt)*);
+                    token!(@parse file, code_remaining, pos, $name $(($value))? $debugname $($tt)*);
                 )*
 
                 let le_problem_place = &code_remaining[..1.max(code_remaining.find(char::is_whitespace).unwrap_or(code_remaining.len()))];
 
                 Result(Err(Error {
-                    span: Span::extend_by_one(*pos),
+                    span: Span::extend_by_one(file, *pos),
                     message: String::from(concat!("expected one of ", token!(@message $( [$name $(($value))? $debugname $($tt)* ] )*))),
-                    clarifying: format!("...but got `{le_problem_place}`"),
-                    filename: filename.to_string(),
-                    code: full_code.to_string()
+                    clarifying: format!("...but got `{le_problem_place}`")
                 }))
             }
 
@@ -77,15 +149,30 @@ macro_rules! token {
         }
 
         impl <'code> ParseInput <'code> {$(
-            pub fn $fn_name <'s> (&'s mut self) -> Result <&'s Token <'code>> {
+            pub fn $fn_name(&mut self) -> Result <&'code Token <'code>> {
                 let next = self.peek($debugname)?;
+                let buf = self.stream.buf;
 
-                if matches!(&self.stream.buf[next].kind, token!(@matches $name $($value)?)) {
+                if matches!(&buf[next].kind, token!(@matches $name $($value)?)) {
                     self.go_forward();
-                    return Result(Ok(&self.stream.buf[next]))
+                    return Result(Ok(&buf[next]))
                 }
 
-                self.generate_expected_err(concat!('`', $debugname, '`'), &self.stream.buf[next])
+                self.generate_expected_err(concat!('`', $debugname, '`'), &buf[next])
+            }
+        )*
+
+        $(
+            pub fn $synthetic_fn_name(&mut self) -> Result <&'code Token <'code>> {
+                let next = self.peek($synthetic_debugname)?;
+                let buf = self.stream.buf;
+
+                if buf[next].kind == TokenKind::$synthetic {
+                    self.go_forward();
+                    return Result(Ok(&buf[next]))
+                }
+
+                self.generate_expected_err($synthetic_debugname, &buf[next])
             }
         )*}
     };
@@ -121,7 +208,7 @@ macro_rules! token {
         if let Self::$name(val) = $self {
             $f.write_fmt(format_args!("\"{}\"", val))
         } else {
-            token!(@debug $f, $( $tail )*);
+            token!(@debug $f, $( $tail )*)
         }
     };
 
@@ -149,44 +236,28 @@ macro_rules! token {
         $debugname
     };
 
-    (@messageSingle $name:ident $pat:literal, newlines = 1 $( $tt:tt )*) => {
+    (@messageSingle Newline $pat:literal $( $tt:tt )*) => {
         "a newline character"
     };
 
-    (@messageSingle Tab $pat:literal $( $tt:tt )*) => {
-        "a tab character"
-    };
-
     (@messageSingle $name:ident $pat:literal $( $tt:tt )*) => {
         concat!('`', $pat, '`')
     };
 
-    (@parse $textcode:ident, $pos:ident, $name:ident($vl:ty) $debugname:literal $( $code:tt )*) => {
+    (@parse $file:ident, $textcode:ident, $pos:ident, $name:ident($vl:ty) $debugname:literal $( $code:tt )*) => {
         if let Some(len) = ($( $code )*)($textcode) {
-            return Result(Ok(parse_one::<0>($pos, len, TokenKind::$name(&$textcode[..len as usize]), $textcode)))
+            return Result(Ok(parse_one($file, $pos, len, TokenKind::$name(&$textcode[..len as usize]), $textcode)))
         }
     };
 
-    (@parse $code:ident, $pos:ident, $name:ident $pat:literal $( , newlines = $newlines:literal )?) => {
+    (@parse $file:ident, $code:ident, $pos:ident, $name:ident $pat:literal) => {
         if $code.starts_with($pat) {
-            const EIZ: u32 = token!(@emptyIsZero $( $newlines )?);
-            return Result(Ok(parse_one::<EIZ>($pos, $pat.len() as u32, TokenKind::$name, $code)))
+            return Result(Ok(parse_one($file, $pos, $pat.len() as u32, TokenKind::$name, $code)))
         }
     };
-
-    (@emptyIsZero) => {
-        0
-    };
-
-    (@emptyIsZero $literal:literal) => {
-        $literal
-    };
 }
 
 token! {
-    /// The tab character
-    Tab[tab]["\t"]
-
     /// (
     OpenBrace[open_brace]["("]
 
@@ -199,9 +270,30 @@ token! {
     /// ->
     Arrow[arrow]["->"]
 
+    /// =>
+    FatArrow[fat_arrow]["=>"]
+
+    /// ::
+    ColonColon[colon_colon]["::"]
+
     /// :
     TwoDots[two_dots][":"]
 
+    /// .
+    Dot[dot]["."]
+
+    /// @
+    At[at]["@"]
+
+    /// ~
+    Tilde[tilde]["~"]
+
+    /// ≁
+    NotTilde[not_tilde]["≁"]
+
+    /// ?
+    Question[question]["?"]
+
     /// +
     Plus[plus]["+"]
 
@@ -217,23 +309,104 @@ token! {
     /// ,
     Comma[comma][","]
 
+    /// ==
+    EqEq[eq_eq]["=="]
+
+    /// !=
+    NotEq[not_eq]["!="]
+
+    /// <=
+    LessEq[less_eq]["<="]
+
+    /// >=
+    GreaterEq[greater_eq][">="]
+
+    /// <
+    Less[less]["<"]
+
+    /// >
+    Greater[greater][">"]
+
+    /// &&
+    AndAnd[and_and]["&&"]
+
+    /// ||
+    OrOr[or_or]["||"]
+
+    /// !
+    Exclamation[exclamation]["!"]
+
     /// =
     Eq[eq]["="]
 
     /// The newline character
-    Newline[newline]["\n", newlines = 1]
+    Newline[newline]["\n"]
 
-    /// The string that starts with an alphabetic character and continues with alphanumeric
+    /// The doc comment, which starts with `;;` and lasts until the end of the line, excluding the newline
+    ///
+    /// It documents the item written right after it, see [`Doc`].
+    /// A doc comment that does not start its line is an ordinary comment, [`TokenStream::new`] drops it
     ///
-    /// Examples: `H`, `twentySeven`, `mom`, `abc1234`, `ja83n82bjd9q`
+    /// Examples: `;; Returns the sum of the numbers`
+    DocComment(&'code str)[doc_comment]["a doc comment" |code: &str| {
+        if code.starts_with(";;") {
+            Some(code.find('\n').unwrap_or(code.len()) as u32)
+        } else {
+            None
+        }
+    }]
+
+    /// The comment, either a single-line one starting with `;` and lasting until the end of the line,
+    /// or a multiline one enclosed in `;[` and `];`, which may be nested
+    ///
+    /// Comments are trivia, [`TokenStream::new`] drops them after they are parsed
+    ///
+    /// Examples: `; hello`, `;[ hello ;[ nested ]; ];`
+    Comment(&'code str)[comment]["a comment" comment_len]
+
+    /// The integer literal, either decimal, hexadecimal(`0x`) or binary(`0b`),
+    /// digits may be separated by `_`
+    ///
+    /// Validity of the digits is checked later, during the parsing of the literal
+    ///
+    /// Examples: `0`, `42`, `1_000_000`, `0xFF`, `0b1010_0101`
+    Int(&'code str)[int]["an integer literal" |code: &str| {
+        if code.starts_with(|char: char| char.is_ascii_digit()) {
+            Some(code.find(|char: char| !char.is_alphanumeric() && char != '_').unwrap_or(code.len()) as u32)
+        } else {
+            None
+        }
+    }]
+
+    /// The string that starts with an alphabetic character or `_` and continues with alphanumeric or `_`,
+    /// it should have at least one alphanumeric character
+    ///
+    /// Examples: `H`, `twentySeven`, `mom`, `abc1234`, `ja83n82bjd9q`, `__try`
     Ident(&'code str)[ident]["an identifier" |code: &str| {
-        let first = code.chars().next().unwrap();
-        if first.is_alphabetic() {
-            Some(code[first.len_utf8()..].find(|char: char| !char.is_alphanumeric()).unwrap_or(code.len()) as u32 + 1)
+        let len = code.find(|char: char| !char.is_alphanumeric() && char != '_').unwrap_or(code.len());
+        if code.starts_with(|char: char| char.is_alphabetic() || char == '_') && code[..len].contains(char::is_alphanumeric) {
+            Some(len as u32)
         } else {
             None
         }
     }]
+
+    /// _
+    Underscore[underscore]["_"]
+
+    ;
+
+    /// The indentation of the line is one level deeper than the one of the previous line,
+    /// the token is right after the newline and spans the indentation
+    ///
+    /// Produced only by [`TokenStream::new`]
+    Indent[indent]["an indentation"]
+
+    /// The indentation of the line is one level shallower than the one of the previous line,
+    /// there are as many of these right after the newline as the levels closed
+    ///
+    /// Produced only by [`TokenStream::new`]
+    Dedent[dedent]["a dedentation"]
 }
 
 ///
@@ -0,0 +1,207 @@
+mod common;
+
+use common::*;
+use oko::*;
+use std::process::{Command, Output};
+
+/// Compiles the C output for `code` with the system `cc` and runs it
+fn compile_and_run(name: &str, code: &str) -> Output {
+    compile_and_run_modules(name, &[("code", code)], &[])
+}
+
+/// Compiles the C output for the root file and the modules `files` with the system `cc` and the extra `flags` and runs it
+fn compile_and_run_modules(name: &str, files: &[(&str, &str)], flags: &[&str]) -> Output {
+    let dir = std::env::temp_dir().join(format!("oko-c-backend-{}-{name}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let source = dir.join("main.c");
+    let executable = dir.join("main");
+
+    with_baked_modules(files, |input| write_c(input, &source)).unwrap();
+
+    let cc = Command::new("cc")
+        .arg("-std=c11")
+        .args(flags)
+        .arg("-o")
+        .arg(&executable)
+        .arg(&source)
+        .output()
+        .expect("failed to run `cc`");
+
+    assert!(cc.status.success(), "`cc` failed:\n{}", String::from_utf8_lossy(&cc.stderr));
+
+    let output = Command::new(&executable).output().unwrap();
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    output
+}
+
+const PROGRAM: &str = "
+fact n: i32 -> i32
+    if n == 0 then 1 else n * (fact (n - 1))
+
+fib n: i32 -> i32
+    if n < 2
+        n
+    else
+        (fib (n - 1)) + (fib (n - 2))
+
+divmod a b: i32 -> (i32, i32)
+    $q = a / b
+    (q, a - q * b)
+
+even n: i32 -> bool
+    n == 0 || n != 1 && (even (n - 2))
+
+main -> (i32, i32, (i32, i32), bool, bool)
+    $(q r) = divmod 17 5
+    $x = -3
+    $flag = -x > 2 && !false
+    $f = fact 12
+    $g = fib 20
+    $e = even 10
+    (f, g, (q, r), e, flag)
+";
+
+#[test]
+fn output_matches_interpreter() {
+    let expected = with_baked(PROGRAM, |input| format!("{:?}\n", interpret(input).0.unwrap().debug(input)));
+
+    let output = compile_and_run("matches", PROGRAM);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+}
+
+#[test]
+fn runtime_support_is_emitted_only_when_used() {
+    // `-Wall` warns about the static functions nothing calls
+    for (name, code) in [("support-arithmetic", PROGRAM), ("support-none", "main -> (bool, ())\n    (true, ())\n")] {
+        let output = compile_and_run_modules(name, &[("code", code)], &["-Wall", "-Werror"]);
+        assert!(output.status.success());
+    }
+
+    let c = with_baked("main -> bool\n    true\n", transpile_to_c);
+    assert!(!c.contains("oko_panic") && !c.contains("oko_print_i128"));
+}
+
+#[test]
+fn division_by_zero_panics() {
+    let output = compile_and_run("div-by-zero", "
+div a b: i32 -> i32
+    a / b
+
+main -> i32
+    div 1 0
+");
+
+    assert_eq!(output.status.code(), Some(101));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("attempt to divide by zero"));
+}
+
+const MODULES: [(&str, &str); 3] = [("main", "
+use geo
+use net::tcp
+
+ty Point
+    x: i32
+
+half x: i32 -> i32
+    x / 2
+
+main -> (i32, i32, i32, i32)
+    $p = geo::Point::new 3 4
+    ((half 8), (tcp::half 8), (geo::half 8), p.y)
+"), ("geo", "
+ty Point
+    x y: i32
+
+half x: i32 -> i32
+    x / 0
+"), ("net::tcp", "
+half x: i32 -> i32
+    x / 2
+")];
+
+#[test]
+fn modules_do_not_clash() {
+    let output = compile_and_run_modules("modules", &MODULES, &[]);
+
+    assert_eq!(output.status.code(), Some(101));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("attempt to divide by zero at geo.oko:"));
+}
+
+#[test]
+fn arithmetic_overflow_panics_like_the_interpreter() {
+    let cases = [
+        ("add", "add 2147483647 1", "attempt to add with overflow"),
+        ("sub", "sub (-2147483647 - 1) 1", "attempt to subtract with overflow"),
+        ("mul", "mul 65536 65536", "attempt to multiply with overflow"),
+        ("div", "div (-2147483647 - 1) -1", "attempt to divide with overflow"),
+        ("neg", "neg (-2147483647 - 1)", "attempt to negate with overflow")
+    ];
+
+    for (name, call, message) in cases {
+        let code = format!("
+add a b: i32 -> i32
+    a + b
+
+sub a b: i32 -> i32
+    a - b
+
+mul a b: i32 -> i32
+    a * b
+
+div a b: i32 -> i32
+    a / b
+
+neg a: i32 -> i32
+    -a
+
+main -> i32
+    {call}
+");
+
+        let error = with_baked(&code, |input| match interpret(input).0 {
+            Ok(_) => String::new(),
+            Err(error) => error.message
+        });
+        assert!(error.contains(message), "the interpreter failed on `{call}` with `{error}`");
+
+        let output = compile_and_run(&format!("overflow-{name}"), &code);
+
+        assert_eq!(output.status.code(), Some(101), "`{call}` did not stop the C program");
+        assert!(String::from_utf8_lossy(&output.stderr).contains(message));
+    }
+}
+
+const MINIMUM: &str = "
+is_min n: i32 -> bool
+    match n
+        -2147483648 => true
+        _ => false
+
+main -> (i32, bool, bool, i32)
+    $min = -2147483648
+    (min, (is_min min), (is_min -2147483647), - -7)
+";
+
+#[test]
+fn minimum_of_the_type_can_be_written_as_a_literal() {
+    let expected = with_baked(MINIMUM, |input| format!("{:?}\n", interpret(input).0.unwrap().debug(input)));
+    assert_eq!(expected, "(-2147483648, true, false, 7)\n");
+
+    let output = compile_and_run("minimum", MINIMUM);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+}
+
+#[test]
+fn question_mark_returns_the_same_as_in_the_interpreter() {
+    let output = compile_and_run("question-mark", TRY_PROGRAM);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "(3, -1, -1, -1, 5, -1)\n");
+}
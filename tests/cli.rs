@@ -0,0 +1,111 @@
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Returns the path of the file `run_oko` writes the code named `name` into
+fn file_of(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("oko-cli-{}-{name}", std::process::id())).join(format!("{name}.oko"))
+}
+
+/// Writes `code` into a file named `name` in a directory of its own and runs `oko` with `args` followed by the file
+fn run_oko(name: &str, code: &str, args: &[&str]) -> Output {
+    let file = file_of(name);
+    let dir = file.parent().unwrap();
+    std::fs::create_dir_all(dir).unwrap();
+
+    std::fs::write(&file, code).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_oko"))
+        .args(args)
+        .arg(&file)
+        .env_remove("NO_COLOR")
+        .output()
+        .unwrap();
+
+    std::fs::remove_dir_all(dir).unwrap();
+
+    output
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn successful_run_prints_the_value_and_exits_with_zero() {
+    let output = run_oko("success", "main -> (i32, bool)\n    (1 + 2, true)\n", &["run"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "(3, true)\n");
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn compile_errors_exit_with_one() {
+    let output = run_oko("errors", "main -> i32\n    1 + true\n    2 + false\n", &["check", "--color", "never"]);
+
+    assert_eq!(output.status.code(), Some(1));
+
+    let path = file_of("errors").display().to_string();
+
+    assert_eq!(stderr(&output), format!("\
+error: cannot add the `i32` and `bool` types:
+ --> {path}:2:7
+  |
+2 |     1 + true
+  |       ^ incompatible operator and operands
+error: cannot add the `i32` and `bool` types:
+ --> {path}:3:7
+  |
+3 |     2 + false
+  |       ^ incompatible operator and operands
+error: aborting due to 2 errors
+"));
+}
+
+#[test]
+fn colors_are_used_only_when_asked() {
+    let code = "main -> i32\n    1 + true\n";
+
+    assert!(!stderr(&run_oko("no-colors", code, &["check", "--color", "never"])).contains('\x1b'));
+    assert!(stderr(&run_oko("colors", code, &["check", "--color", "always"])).contains('\x1b'));
+
+    // The output of the tests is not a terminal
+    assert!(!stderr(&run_oko("auto-colors", code, &["check"])).contains('\x1b'));
+}
+
+#[test]
+fn warnings_alone_exit_with_zero() {
+    let output = run_oko("warnings", "main -> i32\n    $x = 1\n    2\n", &["check", "--color", "never"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stderr(&output).starts_with("warning: unused variable `x`:"));
+}
+
+#[test]
+fn runtime_errors_exit_with_101() {
+    let output = run_oko("runtime", "div a b: i32 -> i32\n    a / b\n\nmain -> i32\n    div 1 0\n", &["run", "--color", "never"]);
+
+    assert_eq!(output.status.code(), Some(101));
+    assert!(stderr(&output).starts_with("error: attempt to divide by zero:"));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn wrong_arguments_exit_with_two() {
+    for args in [&["launch"][..], &["check", "--color", "sometimes"], &["check", "-o", "out.c"]] {
+        let output = run_oko("arguments", "main -> i32\n    1\n", args);
+        assert_eq!(output.status.code(), Some(2), "{args:?}");
+    }
+}
+
+#[test]
+fn root_file_cannot_be_imported() {
+    let output = run_oko("selfuse", "use selfuse\n\nmain -> i32\n    1\n", &["check", "--color", "never"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("error: the root file cannot be imported as a module:"));
+}
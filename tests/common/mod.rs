@@ -0,0 +1,104 @@
+// Every test file uses only some of the helpers
+#![allow(dead_code)]
+
+use oko::*;
+
+/// Makes the source files of the program of the root file and the modules `files`, each as `(path, code)`,
+/// adding them to the `source_map`
+fn source_files(source_map: &mut SourceMap, files: &[(&str, &str)]) -> Vec <SourceFile> {
+    files.iter().enumerate().map(|(idx, (path, code))| SourceFile::new(
+        source_map,
+        if idx == 0 { vec![] } else { path.split("::").map(String::from).collect() },
+        format!("{path}.oko"),
+        prepare_code(code)
+    )).collect()
+}
+
+/// Bakes `code` the same way the binary does and passes it to `f`
+pub fn with_baked <R> (code: &str, f: impl FnOnce(&ParseInput) -> R) -> R {
+    with_baked_modules(&[("code", code)], f)
+}
+
+/// Bakes the program of the root file and the modules `files`, each as `(path, code)`, and passes it to `f`
+pub fn with_baked_modules <R> (files: &[(&str, &str)], f: impl FnOnce(&ParseInput) -> R) -> R {
+    let mut source_map = SourceMap::new();
+    let files = source_files(&mut source_map, files);
+    compile_program(&source_map, &files, f).0.unwrap()
+}
+
+/// Runs `main` of `code` in the interpreter, returning the value it returned or the message of the runtime error
+pub fn value_of(code: &str) -> String {
+    with_baked(code, |input| match interpret(input).0 {
+        Ok(value) => format!("{:?}", value.debug(input)),
+        Err(error) => error.message
+    })
+}
+
+/// Returns the diagnostics `code` fails to compile with
+pub fn errors_of(code: &str) -> Vec <Diagnostic> {
+    errors_of_modules(&[("code", code)])
+}
+
+/// Returns the diagnostics the program of the root file and the modules `files`, each as `(path, code)`, fails to compile with
+pub fn errors_of_modules(files: &[(&str, &str)]) -> Vec <Diagnostic> {
+    let mut source_map = SourceMap::new();
+    let files = source_files(&mut source_map, files);
+
+    match compile_program(&source_map, &files, |_| ()).0 {
+        Ok(()) => panic!("the code compiled with no errors"),
+        Err(diagnostics) => diagnostics
+    }
+}
+
+/// Compiles `code`, returning whether it compiled and all its diagnostics rendered with no colors
+pub fn rendered_diagnostics(code: &str) -> (bool, String) {
+    let mut source_map = SourceMap::new();
+    let files = source_files(&mut source_map, &[("code", code)]);
+
+    set_colored(false);
+
+    let (compiled, diagnostics) = match compile_program(&source_map, &files, |input| input.diagnostics.iter().map(|diagnostic| diagnostic.render_to_string(&source_map)).collect()).0 {
+        Ok(rendered) => (true, rendered),
+        Err(diagnostics) => (false, diagnostics.iter().map(|diagnostic| diagnostic.render_to_string(&source_map)).collect::<Vec <_>>())
+    };
+
+    (compiled, diagnostics.concat())
+}
+
+/// A program using `?` both when it goes on and when it returns from the function, even from the middle of an expression
+pub const TRY_PROGRAM: &str = "
+enum TryFork <Target, Error>
+    Continue Target
+    Break Error
+
+enum Maybe <T>
+    Nothing
+    Just T
+
+    __try <U> -> TryFork <T, Maybe <U>>
+        match i
+            Nothing => Break::<T, Maybe <U>> Nothing::<U>
+            Just x => Continue::<T, Maybe <U>> x
+
+half x: i32 -> Maybe <i32>
+    if x / 2 * 2 == x then Just (x / 2) else Nothing::<i32>
+
+quarter x: i32 -> Maybe <i32>
+    $h = half x ?
+    half h
+
+eighth x: i32 -> Maybe <i32>
+    $q = quarter x ?
+    half q
+
+one_more_half x: i32 -> Maybe <i32>
+    Just (1 + (half x ?))
+
+unwrap m: Maybe <i32> -> i32
+    match m
+        Nothing => -1
+        Just x => x
+
+main -> (i32, i32, i32, i32, i32, i32)
+    (unwrap (eighth 24), unwrap (eighth 12), unwrap (quarter 6), unwrap (half 7), unwrap (one_more_half 8), unwrap (one_more_half 9))
+";
@@ -0,0 +1,595 @@
+mod common;
+
+use common::*;
+use oko::*;
+
+#[test]
+fn doc_comments_are_attached_to_the_baked_items() {
+    const DOCS: &str = "
+;; A point
+;; on the plane
+ty Point
+    ;; The horizontal one
+    x y: i32
+
+    ;; Moves the point
+    shift d: i32 -> Point
+        ;; ignored in the body
+        Point::new (i.x + d) i.y
+
+main -> i32 ;; not a doc comment
+    $p = Point::new 1 2
+    (p.shift 1).x
+";
+
+    with_baked(DOCS, |input| {
+        let point = match &input.type_bases {
+            TypeBaseContainer::Baked(baked) => baked.iter().find(|base| base.name.data == "Point").unwrap(),
+            _ => unreachable!()
+        };
+
+        assert_eq!(point.doc.text(), "A point\non the plane");
+
+        match &point.kind {
+            BakedTypeBaseKind::TypeProduct(fields) => {
+                assert!(fields.vec.iter().all(|field| field.doc.lines == ["The horizontal one"]))
+            },
+            _ => unreachable!()
+        }
+
+        let docs = input.top_level_items.iter().filter_map(|item| match item {
+            Item::Fn(fun) => Some((fun.name.data, fun.doc.text())),
+            _ => None
+        }).collect::<Vec <_>>();
+
+        assert!(docs.contains(&("shift", String::from("Moves the point"))));
+        assert!(docs.contains(&("main", String::new())));
+    })
+}
+
+#[test]
+fn unterminated_comment_points_at_its_opening() {
+    let errors = errors_of("main -> i32\n    ;[ never closed\n    1\n");
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "unterminated multiline comment");
+    assert_eq!((errors[0].span.start.line, errors[0].span.start.column), (2, 5));
+    assert_eq!(errors[0].span.end.column, 7);
+}
+
+#[test]
+fn operators_split_by_multiline_comments_do_not_overflow_columns() {
+    // The operand and the operator are on different lines, so they are not close
+    let errors = errors_of("main -> i32\n    $x = 100000 ;[\n]; + 2\n    x\n");
+    assert_eq!(errors[0].span.start.line, 3);
+
+    let value = with_baked("main -> i32\n    $x = 100000 ;[\n]; + ;[\n]; 2\n    x\n", |input| format!("{:?}", interpret(input).0.unwrap().debug(input)));
+    assert_eq!(value, "100002");
+}
+
+#[test]
+fn types_containing_themselves_through_fields_and_variants_are_reported() {
+    let errors = errors_of("
+ty Tree
+    root: Node
+
+enum Node
+    Leaf
+    Branch Tree
+
+enum List
+    Nil
+    Cons i32 List
+
+ty Fine
+    x: (i32, bool)
+
+main -> i32
+    1
+");
+
+    let messages = errors.iter().map(|err| err.message.as_str()).collect::<Vec <_>>();
+    assert_eq!(messages, [
+        "the type `Tree` contains itself, so it has infinite size",
+        "the type `List` contains itself, so it has infinite size"
+    ]);
+
+    assert_eq!(errors[0].clarifying, "the field `root` contains `Node`");
+    assert_eq!(errors[0].labels.len(), 1);
+    assert_eq!(errors[1].clarifying, "the variant `Cons` contains `List`");
+}
+
+#[test]
+fn negated_literals_are_checked_against_the_minimum() {
+    let errors = errors_of("main -> i32\n    -2147483649\n");
+    assert_eq!(errors[0].message, "integer literal is out of range for `i32`");
+    assert_eq!(errors[0].clarifying, "`-2147483649` does not fit into `i32`");
+
+    let errors = errors_of("main -> i32\n    match 1\n        -2147483649 => 1\n        _ => 0\n");
+    assert_eq!(errors[0].clarifying, "`-2147483649` does not fit into `i32`");
+}
+
+#[test]
+fn statements_after_a_failed_binding_are_still_checked() {
+    let errors = errors_of("
+main -> i32
+    $a = 1 + true
+    $b = 2 + false
+    $c = a + 1
+    $a = 3
+    $d = a + true
+    c
+");
+
+    // `c` depends on the failed `a` and is not reported, the rebound `a` is known again
+    let lines = errors.iter().map(|err| err.span.start.line).collect::<Vec <_>>();
+    assert_eq!(lines, [3, 4, 7]);
+}
+
+#[test]
+fn type_errors_hide_the_errors_in_the_bodies() {
+    let errors = errors_of("
+ty Point
+    x: Missing
+
+main -> i32
+    1 + true
+");
+
+    let messages = errors.iter().map(|err| err.message.as_str()).collect::<Vec <_>>();
+    assert_eq!(messages, ["the type `Missing` has no definition"]);
+}
+
+#[test]
+fn every_compilation_has_its_own_source_map() {
+    let root_file = || {
+        let mut source_map = SourceMap::new();
+        let root = SourceFile::new(&mut source_map, vec![], String::from("code.oko"), String::from("main -> i32\n    1\n"));
+        (root.file, source_map.get(root.file).map(|file| file.line(2).to_string()))
+    };
+
+    // The ids do not depend on the files added by the compilations before
+    assert_eq!(root_file(), root_file());
+    assert_eq!(root_file().1.as_deref(), Some("    1"));
+}
+
+#[test]
+fn warnings_alone_do_not_fail_the_compilation() {
+    let (compiled, rendered) = rendered_diagnostics("
+main -> i32
+    $y = 2
+    1
+");
+
+    assert!(compiled);
+    assert_eq!(rendered, "\
+warning: unused variable `y`:
+ --> code.oko:3:6
+  |
+3 |     $y = 2
+  |      ^ never used
+");
+}
+
+#[test]
+fn errors_are_rendered_with_their_labels_and_notes() {
+    let (compiled, rendered) = rendered_diagnostics("
+ty Tree
+    root: Node
+
+enum Node
+    Leaf
+    Branch Tree
+
+main -> i32
+    1
+");
+
+    assert!(!compiled);
+    assert_eq!(rendered, "\
+error: the type `Tree` contains itself, so it has infinite size:
+ --> code.oko:3:5
+  |
+3 |     root: Node
+  |     ^^^^ the field `root` contains `Node`
+7 |     Branch Tree
+  |     ------ ...whose variant `Branch` of `Node` contains `Tree`
+  = help: remove a field or a payload of a variant of the cycle, there are no boxes or references to break it with yet
+");
+}
+
+#[test]
+fn unsigned_negation_and_mixed_numeric_types_are_rejected() {
+    let errors = errors_of("
+f x: u8 -> u8
+    -x
+
+g x: u8, y: i32 -> i32
+    y + x
+
+h x: f64 -> f64
+    -x * (-x)
+
+main -> i32
+    1
+");
+
+    let messages = errors.iter().map(|err| err.message.as_str()).collect::<Vec <_>>();
+    assert_eq!(messages, ["cannot negate the `u8` type", "cannot add the `i32` and `u8` types"]);
+}
+
+#[test]
+fn comparisons_and_logical_operators_are_typed() {
+    for (code, message) in [
+        ("1 < 2 < 3", "cannot compare the `bool` and `i32` types"),
+        ("1 && true", "cannot conjunct the `i32` and `bool` types"),
+        ("false || 0", "cannot disjunct the `bool` and `i32` types"),
+        ("!1 == 2", "cannot invert the `i32` type")
+    ] {
+        let errors = errors_of(&format!("main -> bool\n    {code}\n"));
+        assert_eq!(errors[0].message, message, "`{code}`");
+    }
+
+    let value = with_baked("main -> bool\n    1 < 2 == true && !false || 1 != 1\n", |input| format!("{:?}", interpret(input).0.unwrap().debug(input)));
+    assert_eq!(value, "true");
+}
+
+#[test]
+fn if_conditions_and_branches_are_typed() {
+    for (code, message) in [
+        ("if 1 then 2 else 3", "type mismatch: the condition should have `bool` type"),
+        ("if true then 2 else false", "type mismatch: all the branches of `if` should have the same type"),
+        ("if true then 2", "type mismatch: `if` without `else` should have `()` type")
+    ] {
+        let errors = errors_of(&format!("main -> i32\n    {code}\n"));
+        assert_eq!(errors[0].message, message, "`{code}`");
+    }
+}
+
+#[test]
+fn binding_patterns_are_checked_against_the_value() {
+    for (pattern, value, message) in [
+        ("(a b)", "(1, 2, 3)", "type mismatch: cannot destructure `(i32, i32, i32)` into a tuple of 2 elements"),
+        ("(a b)", "1", "type mismatch: cannot destructure `i32` into a tuple of 2 elements"),
+        ("(a (b a))", "(1, (2, 3))", "`a` is bound more than once in the same pattern")
+    ] {
+        let errors = errors_of(&format!("main -> i32\n    ${pattern} = {value}\n    a\n"));
+        assert_eq!(errors[0].message, message, "`{pattern}`");
+    }
+
+    // The variables bound in a block are not seen after it
+    let errors = errors_of("
+main -> i32
+    $y = if true
+        $z = 1
+        z
+    else
+        2
+    z
+");
+    assert_eq!(errors[0].span.start.line, 8);
+}
+
+#[test]
+fn variants_in_several_enums_need_the_enum_written() {
+    const ENUMS: &str = "
+enum Left
+    A
+    B i32
+
+enum Right
+    A
+    C
+
+main -> i32
+    $x = {}
+    1
+";
+
+    let errors = errors_of(&ENUMS.replace("{}", "A"));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "the variant `A` is ambiguous: it is in both `Left` and `Right`");
+    assert_eq!(errors[0].clarifying, "help: write the enum explicitly, like `Left::A`");
+
+    assert!(rendered_diagnostics(&ENUMS.replace("{}", "Right::A")).0);
+    assert!(rendered_diagnostics(&ENUMS.replace("{}", "C")).0);
+
+    let errors = errors_of(&ENUMS.replace("{}", "Left::C"));
+    assert_eq!(errors[0].message, "no variant named `C` in the enum `Left`");
+}
+
+#[test]
+fn variant_payloads_are_checked_against_the_declared_types() {
+    for (code, message) in [
+        ("Pair 1", "wrong number of arguments"),
+        ("Pair 1 true false", "wrong number of arguments"),
+        ("Pair true 1", "wrong type of the argument"),
+        ("Pair 1 2", "wrong type of the argument")
+    ] {
+        let errors = errors_of(&format!("enum Shape\n    Empty\n    Pair i32 bool\n\nmain -> i32\n    $s = {code}\n    1\n"));
+        assert_eq!(errors.len(), 1, "{code}");
+        assert_eq!(errors[0].message, message, "{code}");
+    }
+
+    let errors = errors_of("enum Shape\n    Empty\n    Pair i32 bool\n\nmain -> i32\n    $s = Pair 1 2\n    1\n");
+    assert_eq!(errors[0].clarifying, "expected `bool`, got `i32`");
+}
+
+#[test]
+fn missing_cases_of_match_are_listed() {
+    const TYPES: &str = "
+enum Opt
+    Nothing
+    Just bool
+
+ty Pair
+    x: i32
+    y: bool
+";
+
+    for (ty, arms, missing) in [
+        ("Opt", "Nothing => 0\n        Just true => 1", "`Just false`"),
+        ("Opt", "Just _ => 0", "`Nothing`"),
+        ("(bool, (bool, bool))", "(true, _) => 0\n        (false, (true, _)) => 1", "`(false, (false, _))`"),
+        ("Pair", "Pair _ true => 0", "`Pair _ false`"),
+        ("(Opt, Pair)", "(Nothing, _) => 0\n        (Just _, Pair 0 _) => 1", "`(Just _, Pair _ _)`"),
+        ("bool", "true => 0", "`false`"),
+        ("i32", "0 => 0\n        1 => 1", "`_`")
+    ] {
+        let errors = errors_of(&format!("{TYPES}\nget v: {ty} -> i32\n    match v\n        {arms}\n\nmain -> i32\n    1\n"));
+        assert_eq!(errors.len(), 1, "{ty}");
+        assert_eq!(errors[0].message, format!("non-exhaustive patterns: {missing} not covered"), "{ty}");
+    }
+}
+
+#[test]
+fn arms_covered_by_the_ones_above_are_warned_about() {
+    let (compiled, rendered) = rendered_diagnostics("
+main -> i32
+    match (true, 1)
+        (true, _) => 0
+        (_, 1) => 1
+        (true, 2) => 2
+        (_, _) => 3
+        _ => 4
+");
+
+    assert!(compiled);
+
+    // Both `(true, 2)` and the final `_` are unreachable
+    assert_eq!(rendered.matches("warning: unreachable pattern").count(), 2);
+    assert!(rendered.contains("6 |         (true, 2) => 2\n"));
+    assert!(rendered.contains("8 |         _ => 4\n"));
+}
+
+#[test]
+fn type_arguments_are_inferred_or_given_explicitly() {
+    const MAYBE: &str = "
+enum Maybe <T>
+    Nothing
+    Just T
+
+first <A, B> p: (A, B) -> A
+    match p
+        (a, _) => a
+
+main -> i32
+    {}
+";
+
+    for (code, message, clarifying) in [
+        ("$n = Nothing\n    1", "cannot infer the type parameter `T` of `Nothing`", None),
+        ("first::<i32> (1, 2)", "wrong number of type arguments for `first`", Some("expected `2`, got `1`")),
+        ("first::<i32, i32> (1, true)", "wrong type of the argument", Some("expected `(i32, i32)`, got `(i32, bool)`")),
+        ("first (true, 1)", "return type mismatch", Some("expected `i32`, got `bool`"))
+    ] {
+        let errors = errors_of(&MAYBE.replace("{}", code));
+        assert_eq!(errors.len(), 1, "{code}");
+        assert_eq!(errors[0].message, message, "{code}");
+
+        if let Some(clarifying) = clarifying {
+            assert_eq!(errors[0].clarifying, clarifying, "{code}")
+        }
+    }
+
+    assert!(rendered_diagnostics(&MAYBE.replace("{}", "$n = Nothing::<bool>\n    first::<i32, Maybe <bool>> (1, n)")).0);
+}
+
+#[test]
+fn instances_of_a_function_are_limited() {
+    // The body with errors before the generic one does not stop its instances from being checked
+    let errors = errors_of("
+broken -> i32
+    true
+
+nest <T> x: T -> i32
+    nest (x,)
+
+main -> i32
+    nest 1
+");
+
+    let messages = errors.iter().map(|err| err.message.as_str()).collect::<Vec <_>>();
+    assert_eq!(messages, ["return type mismatch", "`nest` is instantiated with too many or too long types"]);
+}
+
+#[test]
+fn generic_types_contain_themselves_only_with_their_parameters() {
+    let errors = errors_of("
+enum List <T>
+    Nil
+    Cons T List <(T, T)>
+
+main -> i32
+    1
+");
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "the generic type `List` contains itself with other type arguments");
+    assert_eq!(errors[0].clarifying, "help: use the parameters as they are, like `List <T>`");
+}
+
+#[test]
+fn default_new_attribute_controls_the_generated_function() {
+    const POINT: &str = "
+@default new = {}
+ty Point
+    x: i32
+
+    statik origin -> Point
+        Point::new 0
+
+main -> i32
+    (Point::new 1).x
+";
+
+    let errors = errors_of(&POINT.replace("{}", "none"));
+    assert_eq!(errors[0].message, "no function named `new` in the type `Point`");
+
+    let errors = errors_of(&POINT.replace("{}", "private"));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "the function `Point::new` is private");
+    assert_eq!(errors[0].clarifying, "it can be called only by the functions of `Point`");
+
+    assert!(rendered_diagnostics(&POINT.replace("{}", "public")).0);
+
+    let errors = errors_of(&POINT.replace("{}", "sometimes"));
+    assert_eq!(errors[0].message, "unknown value `sometimes` of `@default new`");
+    assert_eq!(errors[0].clarifying, "help: it can be `none`, `private` or `public`");
+}
+
+#[test]
+fn unknown_fields_are_reported_with_the_existing_ones() {
+    let errors = errors_of("
+ty Point
+    x: i32
+
+main -> i32
+    $p = Point::new 1
+    p.y
+");
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "no field or method `y` in the type `Point`");
+    assert_eq!(errors[0].clarifying, "the fields are `x`");
+}
+
+#[test]
+fn functions_of_a_type_do_not_share_names_with_its_members() {
+    for (code, line) in [
+        ("ty Point\n    x: i32\n\n    x -> i32\n        1\n", 2),
+        ("enum E\n    A\n\n    f -> i32\n        1\n\n    statik f -> i32\n        2\n", 4),
+        ("enum E\n    A\n\n    A -> i32\n        3\n", 2)
+    ] {
+        let errors = errors_of(&format!("{code}\nmain -> i32\n    1\n"));
+        assert_eq!(errors.len(), 1, "{code}");
+        assert!(errors[0].message.starts_with("duplicating members:"), "{code}");
+        assert!(errors[0].message.ends_with(&format!("at {line}:5...")), "{code}");
+    }
+}
+
+#[test]
+fn private_new_cannot_be_used_from_other_modules() {
+    const GEO: (&str, &str) = ("geo", "
+@default new = private
+ty Point
+    x: i32
+
+    statik origin -> Point
+        Point::new 0
+");
+
+    for main in ["use geo\n\nmain -> i32\n    (geo::Point::new 1).x\n", "use geo::Point::new\n\nmain -> i32\n    1\n"] {
+        let errors = errors_of_modules(&[("main", main), GEO]);
+        assert_eq!(errors.len(), 1, "{main}");
+        assert_eq!(errors[0].message, "the function `Point::new` is private", "{main}");
+    }
+
+    let value = with_baked_modules(&[("main", "use geo\n\nmain -> i32\n    (geo::Point::origin).x\n"), GEO], |input| {
+        format!("{:?}", interpret(input).0.unwrap().debug(input))
+    });
+
+    assert_eq!(value, "0");
+}
+
+#[test]
+fn tilde_needs_a_call_and_not_tilde_needs_a_tilde() {
+    for (code, message) in [
+        ("add 1 ~", "`~` needs a call after it"),
+        ("(add 1 ~, 2)", "`~` needs a call after it"),
+        ("add 1 ≁ 2", "unmatched `≁`"),
+        ("add ~ neg 1 ≁ 2 ≁", "unmatched `≁`")
+    ] {
+        let errors = errors_of(&format!("add a b: i32 -> i32\n    a + b\n\nneg a: i32 -> i32\n    -a\n\nmain -> i32\n    $x = {code}\n    1\n"));
+        assert_eq!(errors.len(), 1, "{code}");
+        assert_eq!(errors[0].message, message, "{code}");
+    }
+}
+
+#[test]
+fn the_crate_forbids_unsafe_code() {
+    for root in ["src/lib.rs", "src/main.rs"] {
+        let code = std::fs::read_to_string(std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(root)).unwrap();
+        assert!(code.lines().any(|line| line == "#![forbid(unsafe_code)]"), "{root}");
+    }
+}
+
+#[test]
+fn chained_operators_fold_to_the_left() {
+    let value = value_of("
+sub a b: i32 -> i32
+    a - b
+
+main -> (i32, i32, i32, i32, bool, i32)
+    $a = 10 - 3 - 2
+    $b = 100 / 10 / 5
+    $c = 1 + 2 * 3 - 4 / 2
+    $d = - -5
+    $e = 1 < 2 && 2 < 3 || false
+    $f = sub 10 -1
+    (a, b, c, d, e, f)
+");
+
+    assert_eq!(value, "(5, 2, 5, 5, true, 11)");
+}
+
+#[test]
+fn indentation_unit_is_detected_per_file() {
+    let files = [
+        ("main", "\u{feff}use geo\r\n\r\nmain -> (i32, i32)\r\n\tif true\r\n\t\t; the comment does not change the indentation\r\n\t\t((geo::half 8), (geo::half 6))\r\n\telse\r\n\t\t(0, 0)\r\n"),
+        ("geo", "half x: i32 -> i32\n  match x\n    8 => 4\n    _ =>\n      x / 2\n")
+    ];
+
+    let value = with_baked_modules(&files, |input| format!("{:?}", interpret(input).0.unwrap().debug(input)));
+    assert_eq!(value, "(4, 3)");
+}
+
+#[test]
+fn wrong_indentation_is_reported() {
+    for (code, line, message, clarifying) in [
+        (
+            "main -> i32\n    if true\n\t1\n    else\n        2\n",
+            3,
+            "mixed indentation",
+            "this line is indented with tabs, but the indentation unit is 4 spaces"
+        ),
+        (
+            "main -> i32\n    if true\n        1\n  else\n        2\n",
+            4,
+            "misaligned indentation",
+            "this line is indented by 2 spaces, which is not a multiple of the indentation unit of 4 spaces"
+        ),
+        (
+            "main -> i32\n    if true\n            1\n    else\n        2\n",
+            3,
+            "unexpected indentation",
+            "this line is indented by 3 levels, but the previous one only by 1"
+        )
+    ] {
+        let errors = errors_of(code);
+        assert_eq!(errors.len(), 1, "{code}");
+        assert_eq!(errors[0].message, message, "{code}");
+        assert_eq!(errors[0].clarifying, clarifying, "{code}");
+        assert_eq!(errors[0].span.start.line, line, "{code}");
+    }
+}
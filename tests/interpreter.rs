@@ -0,0 +1,160 @@
+mod common;
+
+use common::*;
+use oko::*;
+
+/// Runs `main` calling a recursive function `depth` calls deep with the stack of `stack_size` bytes,
+/// returning its value or the error message
+fn run_recursion(depth: usize, stack_size: usize) -> String {
+    let code = format!("
+count n: i32 -> i32
+    if n == 0 then 0 else 1 + (count (n - 1))
+
+main -> i32
+    count {depth}
+");
+
+    with_baked(&code, |input| match interpret_with_stack_size(input, stack_size).0 {
+        Ok(value) => format!("{:?}", value.debug(input)),
+        Err(error) => error.message
+    })
+}
+
+#[test]
+fn deep_recursion_is_reported_instead_of_overflowing_the_host_stack() {
+    assert_eq!(run_recursion(1000, DEFAULT_STACK_SIZE), "1000");
+    assert_eq!(run_recursion(100_000_000, DEFAULT_STACK_SIZE), "stack overflow");
+
+    // The limit follows the stack, not a number of calls
+    assert_eq!(run_recursion(1000, 1024 * 1024), "stack overflow");
+}
+
+#[test]
+fn inline_and_indented_if_pick_the_right_branch() {
+    let value = value_of("
+sign n: i32 -> i32
+    if n < 0 then -1 else if n == 0 then 0 else 1
+
+clamp n: i32 -> i32
+    if n > 10
+        10
+    else if n < 0
+        $zero = 0
+        zero
+    else
+        n
+
+main -> (i32, i32, i32, i32, i32, i32)
+    ((sign -5), (sign 0), (sign 3), (clamp 42), (clamp -3), (clamp 7))
+");
+
+    assert_eq!(value, "(-1, 0, 1, 10, 0, 7)");
+}
+
+#[test]
+fn bindings_destructure_tuples_and_shadow_earlier_ones() {
+    let value = value_of("
+main -> (i32, i32, i32, bool, i32)
+    $x = 1
+    $(a (b c)) = (2, (3, true))
+    $x = x + a
+    $y = if c
+        $x = 100
+        x
+    else
+        0
+    (x, a, b, c, y)
+");
+
+    // The `x` bound in the branch is not seen after it
+    assert_eq!(value, "(3, 2, 3, true, 100)");
+}
+
+#[test]
+fn generic_functions_and_types_get_an_instance_per_type_arguments() {
+    let value = value_of("
+enum Maybe <T>
+    Nothing
+    Just T
+
+ty Pair <A, B>
+    first: A
+    second: B
+
+first <A, B> p: Pair <A, B> -> A
+    match p
+        Pair a _ => a
+
+or_else <T> m: Maybe <T>, fallback: T -> T
+    match m
+        Nothing => fallback
+        Just x => x
+
+main -> (i32, bool, i32, bool, Maybe <i32>)
+    $p = Pair::new 1 true
+    $q = Pair::new::<bool, i32> false 2
+    ((first p), (first q), (or_else (Just 5) 0), (or_else Nothing::<bool> true), (Nothing::<i32>))
+");
+
+    assert_eq!(value, "(1, false, 5, true, Nothing)");
+}
+
+#[test]
+fn methods_get_the_value_as_i_and_statik_functions_do_not() {
+    let value = value_of("
+enum Shape
+    Square i32
+    Rect i32 i32
+
+    area -> i32
+        match i
+            Square a => a * a
+            Rect w h => w * h
+
+    statik unit -> Shape
+        Square 1
+
+ty Point
+    x y: i32
+
+    statik origin -> Point
+        Point::new 0 0
+
+    shift d: i32 -> Point
+        Point::new (i.x + d) (i.y + d)
+
+    sum -> i32
+        i.x + i.y
+
+main -> (i32, i32, i32, i32, i32)
+    $p = (Point::origin).shift 2
+    $q = Point::shift p 3
+    (p.sum, q.sum, (p.shift 1).x, (Rect 2 3).area, (Shape::unit).area)
+");
+
+    assert_eq!(value, "(4, 10, 3, 6, 1)");
+}
+
+#[test]
+fn tilde_wraps_the_rest_of_the_arguments() {
+    let value = value_of("
+add a b: i32 -> i32
+    a + b
+
+neg a: i32 -> i32
+    -a
+
+main -> (i32, i32, i32, i32, i32)
+    $a = add 1 ~ neg ~ add 2 3
+    $b = add ~ neg 1 ≁ 10
+    $c = add 1 (add ~ neg 2 ≁ 3)
+    (a, b, c, add 1 ~ neg 2, neg 3)
+");
+
+    assert_eq!(value, "(-4, 9, 2, -1, -3)");
+}
+
+#[test]
+fn question_mark_goes_on_or_returns_from_the_function() {
+    assert_eq!(value_of(TRY_PROGRAM), "(3, -1, -1, -1, 5, -1)");
+}